@@ -1,7 +1,6 @@
-use crate::{
-    Piece, Square, FILE_A, FILE_B, FILE_C, FILE_D, FILE_E, FILE_F, FILE_G, FILE_H, RANK_1, RANK_2,
-    RANK_3, RANK_4, RANK_5, RANK_6, RANK_7, RANK_8,
-};
+use std::time::Duration;
+
+use crate::{Piece, Square, RANK_1, RANK_8};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ChessMove {
@@ -12,9 +11,13 @@ pub struct ChessMove {
     to: Square,
     piece: Piece,
     captured: Option<Piece>,
+    captured_square: Option<Square>,
     promotion: Option<Piece>,
     san: String,
     castling: Option<CastlingType>,
+    en_passant: bool,
+    double_push: bool,
+    clock: Option<Duration>,
 }
 
 impl ChessMove {
@@ -25,6 +28,7 @@ impl ChessMove {
         after: String,
         from: u64,
         captured: Option<Piece>,
+        captured_square: Option<u64>,
     ) -> Self {
         Self {
             color,
@@ -34,16 +38,190 @@ impl ChessMove {
             to: Square::from(san.to),
             piece: san.piece,
             captured,
+            captured_square: captured_square.map(Square::from),
             promotion: san.promotion,
-            san: san.san.to_string(),
+            san: san.san.clone(),
             castling: san.castling,
+            en_passant: captured_square.is_some_and(|square| square != san.to),
+            double_push: san.piece == Piece::PAWN && (san.to == from << 16 || san.to == from >> 16),
+            clock: None,
+        }
+    }
+
+    /// Returns the FEN of the position before the move.
+    pub fn before(&self) -> &str {
+        &self.before
+    }
+
+    /// Returns the FEN of the position after the move.
+    pub fn after(&self) -> &str {
+        &self.after
+    }
+
+    /// Returns the move in standard algebraic notation.
+    pub fn san(&self) -> &str {
+        &self.san
+    }
+
+    /// Returns the square the piece moved from. For castling this is the king's square.
+    pub fn from_square(&self) -> Square {
+        self.from
+    }
+
+    /// Returns the square the piece moved to. For castling this is the king's destination.
+    pub fn to_square(&self) -> Square {
+        self.to
+    }
+
+    /// Returns the piece that moved. For promotions this is the pawn.
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    /// Returns whether both moves move the same piece between the same squares with the same
+    /// promotion or castling, however they were written.
+    pub fn same_move(&self, other: &ChessMove) -> bool {
+        self.from == other.from
+            && self.to == other.to
+            && self.piece == other.piece
+            && self.promotion == other.promotion
+            && self.castling == other.castling
+    }
+
+    pub(crate) fn to_move_info(&self) -> MoveInfo {
+        MoveInfo {
+            from: self.from,
+            to: self.to,
+            piece: self.piece,
+            captured: self.captured,
+            promotion: self.promotion,
+            castling: self.castling,
+            en_passant: self.en_passant,
         }
     }
+
+    pub(crate) fn set_san(&mut self, san: String) {
+        self.san = san;
+    }
+
+    pub(crate) fn to_san_move(&self) -> SanMove {
+        SanMove {
+            san: self.san.clone(),
+            piece: self.piece,
+            to: self.to.into(),
+            from: self.from.into(),
+            promotion: self.promotion,
+            castling: self.castling,
+        }
+    }
+
+    /// Returns the clock time remaining after the move, if one was recorded.
+    pub fn clock(&self) -> Option<Duration> {
+        self.clock
+    }
+
+    pub(crate) fn set_clock(&mut self, clock: Duration) {
+        self.clock = Some(clock);
+    }
+
+    /// Returns whether the move was an en passant capture.
+    pub fn is_en_passant(&self) -> bool {
+        self.en_passant
+    }
+
+    /// Returns whether the move was a pawn advancing two squares.
+    pub fn is_double_push(&self) -> bool {
+        self.double_push
+    }
+
+    /// Returns the square of the captured piece, if the move was a capture.
+    /// For en passant this is the square of the removed pawn, not the destination.
+    pub fn captured_square(&self) -> Option<Square> {
+        self.captured_square
+    }
 }
 
+/// A legal move of the current position, before it is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveInfo {
+    pub from: Square,
+    pub to: Square,
+    pub piece: Piece,
+    pub captured: Option<Piece>,
+    /// The piece a pawn promotes to. Each promoting move is listed once per promotion piece.
+    pub promotion: Option<Piece>,
+    pub castling: Option<CastlingType>,
+    /// Whether the move is a pawn capturing en passant.
+    pub en_passant: bool,
+}
+
+/// An error returned when a move can't be played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// The move isn't valid standard algebraic notation.
+    InvalidSan(SanParseError),
+    /// The move isn't valid UCI long algebraic notation.
+    InvalidUci,
+    /// The move isn't legal in the current position.
+    IllegalMove,
+    /// A pawn reaches the last rank but no promotion piece was given.
+    PromotionRequired,
+    /// More than one piece can make the move, so it needs disambiguation.
+    AmbiguousMove,
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MoveError::InvalidSan(error) => write!(f, "Invalid SAN move: {}", error),
+            MoveError::InvalidUci => write!(f, "Invalid UCI move"),
+            MoveError::IllegalMove => write!(f, "Illegal move"),
+            MoveError::PromotionRequired => write!(f, "Promotion piece required"),
+            MoveError::AmbiguousMove => write!(f, "Ambiguous move"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+impl From<SanParseError> for MoveError {
+    fn from(error: SanParseError) -> Self {
+        MoveError::InvalidSan(error)
+    }
+}
+
+/// An error returned when a move in standard algebraic notation can't be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanParseError {
+    /// The move contains a character that isn't part of SAN.
+    InvalidCharacter(char),
+    /// The castling move isn't `O-O` or `O-O-O`.
+    InvalidCastling,
+    /// The promotion piece is missing, invalid, or the move isn't a pawn move.
+    InvalidPromotion,
+    /// The rank is outside `1` to `8`.
+    InvalidRank,
+    /// The move is empty.
+    Empty,
+}
+
+impl std::fmt::Display for SanParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SanParseError::InvalidCharacter(c) => write!(f, "Invalid character '{}'", c),
+            SanParseError::InvalidCastling => write!(f, "Invalid castling move"),
+            SanParseError::InvalidPromotion => write!(f, "Invalid promotion piece"),
+            SanParseError::InvalidRank => write!(f, "Invalid rank"),
+            SanParseError::Empty => write!(f, "Empty move"),
+        }
+    }
+}
+
+impl std::error::Error for SanParseError {}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SanMove<'a> {
-    pub san: &'a str,
+pub struct SanMove {
+    pub san: String,
     pub piece: Piece,
     pub to: u64,
     pub from: u64,
@@ -51,161 +229,151 @@ pub struct SanMove<'a> {
     pub castling: Option<CastlingType>,
 }
 
-impl<'a> SanMove<'a> {
-    pub fn parse(san: &'a str) -> Result<Self, &'a str> {
-        let mut chars = san.chars().peekable();
+impl SanMove {
+    /// Parses a move in SAN format. The parsed move owns its text, so it can be stored
+    /// independently of the input buffer.
+    pub fn parse(san: &str) -> Result<Self, SanParseError> {
+        Self::parse_with_auto_queen(san, true)
+    }
+
+    /// Parses a move in SAN format, only promoting a pawn that reaches the last rank without a
+    /// promotion piece to a queen when `auto_queen` is set.
+    pub(crate) fn parse_with_auto_queen(
+        san: &str,
+        auto_queen: bool,
+    ) -> Result<Self, SanParseError> {
+        if san.trim().is_empty() {
+            return Err(SanParseError::Empty);
+        }
+
+        // A trailing "(=)" draw offer carries no move information, so it's dropped along with any
+        // annotation glyphs around it. Parentheses anywhere else are rejected.
+        let mut body = san;
+        let body = loop {
+            let trimmed = body.trim_end_matches(['+', '#', '!', '?', '‼', '⁇', '⁉', '⁈', ' ']);
+            match trimmed.strip_suffix("(=)") {
+                Some(rest) => body = rest,
+                None => break trimmed,
+            }
+        };
+
+        let mut chars = body.chars().peekable();
         let mut to = 0;
         let mut from = 0;
         let mut piece = Piece::UNKNOWN;
         let mut promotion = None;
         let mut castling = None;
 
-        loop {
-            if let Some(c) = chars.next() {
-                match c {
-                    'x' | '+' | '#' | '!' | '?' | ' ' => {}
+        while let Some(c) = chars.next() {
+            match c {
+                'x' | '+' | '#' | '!' | '?' | ' ' => {}
 
-                    'O' | '0' => {
-                        if piece == Piece::UNKNOWN {
-                            piece = Piece::KING;
-                        }
+                // Annotation glyphs such as "‼" or "⁉" carry no move information.
+                '‼' | '⁇' | '⁉' | '⁈' => {}
 
-                        let target = c;
-
-                        loop {
-                            if let Some(c) = chars.peek() {
-                                match c {
-                                    '-' => {
-                                        chars.next();
-                                        if let Some(c) = chars.next() {
-                                            if c == target {
-                                                if castling.is_none() {
-                                                    castling = Some(CastlingType::KingSide);
-                                                } else {
-                                                    if castling == Some(CastlingType::KingSide) {
-                                                        castling = Some(CastlingType::QueenSide);
-                                                    } else {
-                                                        return Err("Invalid castling move");
-                                                    }
-                                                }
-                                            } else {
-                                                return Err("Invalid castling move");
-                                            }
-                                        }
-                                    }
-                                    _ => {
-                                        if castling.is_none() {
-                                            return Err("Invalid castling move");
-                                        } else {
-                                            break;
-                                        }
+                'O' | '0' => {
+                    if piece == Piece::UNKNOWN {
+                        piece = Piece::KING;
+                    }
+
+                    let target = c;
+
+                    loop {
+                        if let Some('-') = chars.peek() {
+                            chars.next();
+                            if let Some(c) = chars.next() {
+                                if c == target {
+                                    if castling.is_none() {
+                                        castling = Some(CastlingType::KingSide);
+                                    } else if castling == Some(CastlingType::KingSide) {
+                                        castling = Some(CastlingType::QueenSide);
+                                    } else {
+                                        return Err(SanParseError::InvalidCastling);
                                     }
-                                }
-                            } else {
-                                if castling.is_none() {
-                                    return Err("Invalid castling move");
                                 } else {
-                                    break;
+                                    return Err(SanParseError::InvalidCastling);
                                 }
                             }
+                        } else if castling.is_none() {
+                            return Err(SanParseError::InvalidCastling);
+                        } else {
+                            break;
                         }
                     }
-                    '=' => {
-                        if piece != Piece::PAWN || to == 0 {
-                            return Err("Invalid promotion piece");
-                        }
+                }
+                '=' => {
+                    // A "=" that isn't followed by a piece letter is a draw offer, not a promotion.
+                    match chars.peek() {
+                        Some(potential_piece)
+                            if potential_piece.is_ascii_uppercase()
+                                || ('♔'..='♟').contains(potential_piece) =>
+                        {
+                            if piece != Piece::PAWN || to == 0 {
+                                return Err(SanParseError::InvalidPromotion);
+                            }
 
-                        if let Some(potential_rank) = chars.peek() {
-                            promotion = Some(match potential_rank {
-                                'N' => Piece::KNIGHT,
-                                'B' => Piece::BISHOP,
-                                'R' => Piece::ROOK,
-                                'Q' => Piece::QUEEN,
-                                _ => return Err("Invalid promotion piece"),
+                            promotion = Some(match potential_piece {
+                                'N' | '♘' | '♞' => Piece::KNIGHT,
+                                'B' | '♗' | '♝' => Piece::BISHOP,
+                                'R' | '♖' | '♜' => Piece::ROOK,
+                                'Q' | '♕' | '♛' => Piece::QUEEN,
+                                _ => return Err(SanParseError::InvalidPromotion),
                             });
 
                             chars.next();
                         }
+                        _ => {}
                     }
-                    'a'..='h' => {
-                        if c == 'e' && chars.peek() == Some(&'.') {
+                }
+                // An "e.p." en passant marker is consumed on its own so its "e" isn't read as a file.
+                'e' if chars.peek() == Some(&'.') => {
+                    chars.next();
+                    if chars.peek() == Some(&'p') {
+                        chars.next();
+                        if chars.peek() == Some(&'.') {
                             chars.next();
-                            if chars.peek() == Some(&'p') {
-                                chars.next();
-                                if chars.peek() == Some(&'.') {
-                                    chars.next();
-                                }
-                            }
-                        }
-
-                        if piece == Piece::UNKNOWN {
-                            piece = Piece::PAWN;
                         }
+                    }
+                }
+                'a'..='h' => {
+                    if piece == Piece::UNKNOWN {
+                        piece = Piece::PAWN;
+                    }
 
-                        if let Some(potential_rank) = chars.peek() {
-                            if potential_rank.is_digit(10) {
-                                let file: usize = match c {
-                                    'a' => 0,
-                                    'b' => 1,
-                                    'c' => 2,
-                                    'd' => 3,
-                                    'e' => 4,
-                                    'f' => 5,
-                                    'g' => 6,
-                                    'h' => 7,
-                                    _ => unreachable!(),
-                                };
-
-                                let rank: usize = potential_rank.to_digit(10).unwrap() as usize - 1;
-                                if rank > 7 {
-                                    return Err("Invalid rank");
-                                }
+                    if let Some(potential_rank) = chars.peek() {
+                        if potential_rank.is_ascii_digit() {
+                            let square = Square::from_coords(c, *potential_rank)
+                                .ok_or(SanParseError::InvalidRank)?;
 
-                                to = 1 << (file + rank * 8);
-
-                                chars.next();
-                            } else {
-                                from = match c {
-                                    'a' => FILE_A,
-                                    'b' => FILE_B,
-                                    'c' => FILE_C,
-                                    'd' => FILE_D,
-                                    'e' => FILE_E,
-                                    'f' => FILE_F,
-                                    'g' => FILE_G,
-                                    'h' => FILE_H,
-                                    _ => unreachable!(),
-                                };
+                            // A second square, as in "Nb1d2", makes the first one the origin.
+                            if to != 0 {
+                                from = to;
                             }
+
+                            to = square.into();
+
+                            chars.next();
+                        } else {
+                            from = Square::from_coords(c, '1').unwrap().file_mask();
                         }
                     }
-                    '1'..='8' => {
-                        from = match c {
-                            '1' => RANK_1,
-                            '2' => RANK_2,
-                            '3' => RANK_3,
-                            '4' => RANK_4,
-                            '5' => RANK_5,
-                            '6' => RANK_6,
-                            '7' => RANK_7,
-                            '8' => RANK_8,
-                            _ => unreachable!(),
-                        }
-                    }
-                    'N' | 'B' | 'R' | 'Q' | 'K' => {
-                        piece = match c {
-                            'N' => Piece::KNIGHT,
-                            'B' => Piece::BISHOP,
-                            'R' => Piece::ROOK,
-                            'Q' => Piece::QUEEN,
-                            'K' => Piece::KING,
-                            _ => unreachable!(),
-                        };
-                    }
-                    _ => return Err("Invalid character"),
                 }
-            } else {
-                break;
+                '1'..='8' => {
+                    from = Square::from_coords('a', c).unwrap().rank_mask();
+                }
+                // Figurine notation uses the Unicode piece symbols of either color.
+                'N' | 'B' | 'R' | 'Q' | 'K' | '♔'..='♟' => {
+                    piece = match c {
+                        'N' | '♘' | '♞' => Piece::KNIGHT,
+                        'B' | '♗' | '♝' => Piece::BISHOP,
+                        'R' | '♖' | '♜' => Piece::ROOK,
+                        'Q' | '♕' | '♛' => Piece::QUEEN,
+                        'K' | '♔' | '♚' => Piece::KING,
+                        '♙' | '♟' => Piece::PAWN,
+                        _ => unreachable!(),
+                    };
+                }
+                _ => return Err(SanParseError::InvalidCharacter(c)),
             }
         }
 
@@ -213,14 +381,16 @@ impl<'a> SanMove<'a> {
             to = std::mem::replace(&mut from, 0);
         }
 
-        if piece == Piece::PAWN {
-            if (to & RANK_8 != 0 || to & RANK_1 != 0) && promotion.is_none() {
-                promotion = Some(Piece::QUEEN);
-            }
+        if auto_queen
+            && piece == Piece::PAWN
+            && (to & RANK_8 != 0 || to & RANK_1 != 0)
+            && promotion.is_none()
+        {
+            promotion = Some(Piece::QUEEN);
         }
 
         Ok(Self {
-            san,
+            san: san.to_string(),
             piece,
             to,
             from,
@@ -232,7 +402,7 @@ impl<'a> SanMove<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Square;
+    use crate::{Square, FILE_A, FILE_B, FILE_D, FILE_E, FILE_F, RANK_3};
 
     use super::*;
 
@@ -297,7 +467,10 @@ mod tests {
         assert_eq!(san_move.castling, Some(CastlingType::QueenSide));
 
         let invalid_castle = "O-O-O-O";
-        assert!(SanMove::parse(invalid_castle).is_err());
+        assert_eq!(
+            SanMove::parse(invalid_castle),
+            Err(SanParseError::InvalidCastling)
+        );
 
         let san = "N3d2";
         let san_move = SanMove::parse(san).unwrap();
@@ -315,6 +488,18 @@ mod tests {
         assert_eq!(san_move.from, FILE_E);
         assert_eq!(san_move.promotion, None);
 
+        let san_move = SanMove::parse("dxe3 e.p.").unwrap();
+        assert_eq!(san_move.to, u64::from(Square::E3));
+        assert_eq!(san_move.from, FILE_D);
+
+        let san_move = SanMove::parse("fxe6 e.p.").unwrap();
+        assert_eq!(san_move.to, u64::from(Square::E6));
+        assert_eq!(san_move.from, FILE_F);
+
+        let san_move = SanMove::parse("dxe3 e.p.+").unwrap();
+        assert_eq!(san_move.to, u64::from(Square::E3));
+        assert_eq!(san_move.from, FILE_D);
+
         let san = "e8";
         let san_move = SanMove::parse(san).unwrap();
         assert_eq!(san_move.san, "e8");
@@ -323,6 +508,124 @@ mod tests {
         assert_eq!(san_move.from, 0);
         assert_eq!(san_move.promotion, Some(Piece::QUEEN));
     }
+
+    #[test]
+    fn test_san_move_annotations() {
+        let san_move = SanMove::parse("Qh7++").unwrap();
+        assert_eq!(san_move.piece, Piece::QUEEN);
+        assert_eq!(san_move.to, 1 << Square::H7 as u64);
+        assert_eq!(san_move.from, 0);
+
+        let san_move = SanMove::parse("Nf3‼").unwrap();
+        assert_eq!(san_move.piece, Piece::KNIGHT);
+        assert_eq!(san_move.to, 1 << Square::F3 as u64);
+
+        let san_move = SanMove::parse("Bb5⁉").unwrap();
+        assert_eq!(san_move.piece, Piece::BISHOP);
+        assert_eq!(san_move.to, 1 << Square::B5 as u64);
+
+        let san_move = SanMove::parse("Qd4 (=)").unwrap();
+        assert_eq!(san_move.piece, Piece::QUEEN);
+        assert_eq!(san_move.to, 1 << Square::D4 as u64);
+        assert_eq!(san_move.promotion, None);
+
+        let san_move = SanMove::parse("Qd4!(=)").unwrap();
+        assert_eq!(san_move.to, 1 << Square::D4 as u64);
+
+        let san_move = SanMove::parse("e8=N+=").unwrap();
+        assert_eq!(san_move.promotion, Some(Piece::KNIGHT));
+
+        assert_eq!(
+            SanMove::parse("Nf3$"),
+            Err(SanParseError::InvalidCharacter('$'))
+        );
+        assert!(SanMove::parse("Qd4 (Nf3)").is_err());
+        assert_eq!(
+            SanMove::parse("N(f3)"),
+            Err(SanParseError::InvalidCharacter('('))
+        );
+        assert_eq!(
+            SanMove::parse("Qh7=K"),
+            Err(SanParseError::InvalidPromotion)
+        );
+    }
+
+    #[test]
+    fn test_san_piece_letter_and_b_file() {
+        let san_move = SanMove::parse("Bb5").unwrap();
+        assert_eq!(san_move.piece, Piece::BISHOP);
+        assert_eq!(san_move.to, Square::B5.into());
+        assert_eq!(san_move.from, 0);
+
+        let san_move = SanMove::parse("Bxb5").unwrap();
+        assert_eq!(san_move.piece, Piece::BISHOP);
+        assert_eq!(san_move.to, Square::B5.into());
+        assert_eq!(san_move.from, 0);
+
+        let san_move = SanMove::parse("Rb1").unwrap();
+        assert_eq!(san_move.piece, Piece::ROOK);
+        assert_eq!(san_move.to, Square::B1.into());
+        assert_eq!(san_move.from, 0);
+
+        let san_move = SanMove::parse("Nb1d2").unwrap();
+        assert_eq!(san_move.piece, Piece::KNIGHT);
+        assert_eq!(san_move.to, Square::D2.into());
+        assert_eq!(san_move.from, Square::B1.into());
+
+        let san_move = SanMove::parse("bxc6").unwrap();
+        assert_eq!(san_move.piece, Piece::PAWN);
+        assert_eq!(san_move.to, Square::C6.into());
+        assert_eq!(san_move.from, FILE_B);
+    }
+
+    #[test]
+    fn test_san_parse_errors() {
+        assert_eq!(SanMove::parse(""), Err(SanParseError::Empty));
+        assert_eq!(SanMove::parse("  "), Err(SanParseError::Empty));
+        assert_eq!(SanMove::parse("O-"), Err(SanParseError::InvalidCastling));
+        assert_eq!(SanMove::parse("e9"), Err(SanParseError::InvalidRank));
+        assert_eq!(SanMove::parse("e0"), Err(SanParseError::InvalidRank));
+        assert_eq!(SanMove::parse("e8=X"), Err(SanParseError::InvalidPromotion));
+        assert_eq!(
+            SanMove::parse("Zf3"),
+            Err(SanParseError::InvalidCharacter('Z'))
+        );
+    }
+
+    #[test]
+    fn test_san_move_figurine() {
+        let san_move = SanMove::parse("♞f3").unwrap();
+        assert_eq!(san_move.piece, Piece::KNIGHT);
+        assert_eq!(san_move.to, Square::F3.into());
+
+        let san_move = SanMove::parse("♗xc4+").unwrap();
+        assert_eq!(san_move.piece, Piece::BISHOP);
+        assert_eq!(san_move.to, Square::C4.into());
+
+        let san_move = SanMove::parse("e8=♕").unwrap();
+        assert_eq!(san_move.piece, Piece::PAWN);
+        assert_eq!(san_move.promotion, Some(Piece::QUEEN));
+
+        let san_move = SanMove::parse("exd8=♞").unwrap();
+        assert_eq!(san_move.promotion, Some(Piece::KNIGHT));
+    }
+
+    #[test]
+    fn test_san_move_owned() {
+        let mut moves: Vec<SanMove> = Vec::new();
+
+        {
+            let line = String::from("e4 e5 Nf3 Nc6");
+            for san in line.split_whitespace() {
+                moves.push(SanMove::parse(san).unwrap());
+            }
+        }
+
+        assert_eq!(moves.len(), 4);
+        assert_eq!(moves[2].san, "Nf3");
+        assert_eq!(moves[2].piece, Piece::KNIGHT);
+        assert_eq!(moves[3].to, 1 << Square::C6 as u64);
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
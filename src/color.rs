@@ -0,0 +1,41 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl From<bool> for Color {
+    fn from(white: bool) -> Self {
+        match white {
+            true => Color::White,
+            false => Color::Black,
+        }
+    }
+}
+
+impl From<Color> for bool {
+    fn from(color: Color) -> Self {
+        color == Color::White
+    }
+}
+
+impl std::ops::Not for Color {
+    type Output = Color;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Color::White => "White",
+            Color::Black => "Black",
+        };
+        write!(f, "{}", name)
+    }
+}
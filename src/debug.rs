@@ -0,0 +1,39 @@
+/// The attack masks the move generator works with. Useful for writing generator tests
+/// and for making bug reports reproducible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugMasks {
+    /// Squares attacked by the white pawns, knights and king.
+    pub static_white_attack_mask: u64,
+    /// Squares attacked by the black pawns, knights and king.
+    pub static_black_attack_mask: u64,
+    /// Squares attacked by the white bishops, rooks and queens.
+    pub dynamic_white_attack_mask: u64,
+    /// Squares attacked by the black bishops, rooks and queens.
+    pub dynamic_black_attack_mask: u64,
+}
+
+/// Returns a bitboard as an 8x8 grid of `#` for set squares and `.` for empty ones,
+/// oriented like `Chessboard::ascii` with rank 8 at the top.
+/// # Examples
+/// ```
+/// use w_chess::format_mask;
+/// let grid = format_mask(0xFF);
+/// assert_eq!(grid.lines().last(), Some("# # # # # # # # "));
+/// ```
+pub fn format_mask(mask: u64) -> String {
+    let mut grid = String::new();
+    for rank in 0_u64..8 {
+        for file in 0_u64..8 {
+            let square = 1 << (56 - rank * 8 + file);
+
+            match mask & square != 0 {
+                true => grid.push('#'),
+                false => grid.push('.'),
+            }
+            grid.push(' ');
+        }
+
+        grid.push('\n');
+    }
+    grid
+}
@@ -0,0 +1,251 @@
+use crate::{fen_positions_equal, square::START_FEN, Chessboard};
+
+/// An opening from the ECO classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EcoEntry {
+    code: &'static str,
+    name: &'static str,
+    moves: &'static str,
+}
+
+impl EcoEntry {
+    const fn new(code: &'static str, name: &'static str, moves: &'static str) -> Self {
+        Self { code, name, moves }
+    }
+
+    /// Returns the ECO code, such as `C60`.
+    pub fn code(&self) -> &str {
+        self.code
+    }
+
+    /// Returns the name of the opening, such as `Ruy Lopez`.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Returns the moves defining the opening in SAN, separated by spaces.
+    pub fn moves(&self) -> &str {
+        self.moves
+    }
+}
+
+const ENTRIES: &[EcoEntry] = &[
+    EcoEntry::new("A00", "Polish Opening", "b4"),
+    EcoEntry::new("A01", "Nimzo-Larsen Attack", "b3"),
+    EcoEntry::new("A02", "Bird's Opening", "f4"),
+    EcoEntry::new("A04", "Reti Opening", "Nf3"),
+    EcoEntry::new("A10", "English Opening", "c4"),
+    EcoEntry::new("A20", "English Opening: King's English Variation", "c4 e5"),
+    EcoEntry::new("A40", "Queen's Pawn Game", "d4"),
+    EcoEntry::new("A45", "Indian Defense", "d4 Nf6"),
+    EcoEntry::new("A46", "Indian Defense: Knights Variation", "d4 Nf6 Nf3"),
+    EcoEntry::new("A50", "Indian Defense: Normal Variation", "d4 Nf6 c4"),
+    EcoEntry::new("A56", "Benoni Defense", "d4 Nf6 c4 c5"),
+    EcoEntry::new("A57", "Benko Gambit", "d4 Nf6 c4 c5 d5 b5"),
+    EcoEntry::new("A80", "Dutch Defense", "d4 f5"),
+    EcoEntry::new("B00", "King's Pawn Game", "e4"),
+    EcoEntry::new("B01", "Scandinavian Defense", "e4 d5"),
+    EcoEntry::new("B02", "Alekhine Defense", "e4 Nf6"),
+    EcoEntry::new("B06", "Modern Defense", "e4 g6"),
+    EcoEntry::new("B07", "Pirc Defense", "e4 d6 d4 Nf6"),
+    EcoEntry::new("B10", "Caro-Kann Defense", "e4 c6"),
+    EcoEntry::new("B20", "Sicilian Defense", "e4 c5"),
+    EcoEntry::new(
+        "B21",
+        "Sicilian Defense: Smith-Morra Gambit",
+        "e4 c5 d4 cxd4 c3",
+    ),
+    EcoEntry::new("B22", "Sicilian Defense: Alapin Variation", "e4 c5 c3"),
+    EcoEntry::new("B23", "Sicilian Defense: Closed", "e4 c5 Nc3"),
+    EcoEntry::new("B27", "Sicilian Defense", "e4 c5 Nf3"),
+    EcoEntry::new("B30", "Sicilian Defense: Old Sicilian", "e4 c5 Nf3 Nc6"),
+    EcoEntry::new("B40", "Sicilian Defense: French Variation", "e4 c5 Nf3 e6"),
+    EcoEntry::new("B50", "Sicilian Defense: Modern Variations", "e4 c5 Nf3 d6"),
+    EcoEntry::new("B54", "Sicilian Defense: Open", "e4 c5 Nf3 d6 d4 cxd4 Nxd4"),
+    EcoEntry::new(
+        "B70",
+        "Sicilian Defense: Dragon Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 g6",
+    ),
+    EcoEntry::new(
+        "B90",
+        "Sicilian Defense: Najdorf Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6",
+    ),
+    EcoEntry::new("C00", "French Defense", "e4 e6"),
+    EcoEntry::new(
+        "C01",
+        "French Defense: Exchange Variation",
+        "e4 e6 d4 d5 exd5",
+    ),
+    EcoEntry::new("C02", "French Defense: Advance Variation", "e4 e6 d4 d5 e5"),
+    EcoEntry::new(
+        "C03",
+        "French Defense: Tarrasch Variation",
+        "e4 e6 d4 d5 Nd2",
+    ),
+    EcoEntry::new(
+        "C10",
+        "French Defense: Paulsen Variation",
+        "e4 e6 d4 d5 Nc3",
+    ),
+    EcoEntry::new("C20", "King's Pawn Game", "e4 e5"),
+    EcoEntry::new("C23", "Bishop's Opening", "e4 e5 Bc4"),
+    EcoEntry::new("C25", "Vienna Game", "e4 e5 Nc3"),
+    EcoEntry::new("C30", "King's Gambit", "e4 e5 f4"),
+    EcoEntry::new("C33", "King's Gambit Accepted", "e4 e5 f4 exf4"),
+    EcoEntry::new("C40", "King's Knight Opening", "e4 e5 Nf3"),
+    EcoEntry::new("C41", "Philidor Defense", "e4 e5 Nf3 d6"),
+    EcoEntry::new("C42", "Petrov's Defense", "e4 e5 Nf3 Nf6"),
+    EcoEntry::new(
+        "C44",
+        "King's Knight Opening: Normal Variation",
+        "e4 e5 Nf3 Nc6",
+    ),
+    EcoEntry::new("C44", "Scotch Game", "e4 e5 Nf3 Nc6 d4"),
+    EcoEntry::new("C46", "Three Knights Opening", "e4 e5 Nf3 Nc6 Nc3"),
+    EcoEntry::new("C47", "Four Knights Game", "e4 e5 Nf3 Nc6 Nc3 Nf6"),
+    EcoEntry::new("C50", "Italian Game", "e4 e5 Nf3 Nc6 Bc4"),
+    EcoEntry::new("C50", "Italian Game: Giuoco Piano", "e4 e5 Nf3 Nc6 Bc4 Bc5"),
+    EcoEntry::new(
+        "C51",
+        "Italian Game: Evans Gambit",
+        "e4 e5 Nf3 Nc6 Bc4 Bc5 b4",
+    ),
+    EcoEntry::new(
+        "C55",
+        "Italian Game: Two Knights Defense",
+        "e4 e5 Nf3 Nc6 Bc4 Nf6",
+    ),
+    EcoEntry::new("C60", "Ruy Lopez", "e4 e5 Nf3 Nc6 Bb5"),
+    EcoEntry::new("C65", "Ruy Lopez: Berlin Defense", "e4 e5 Nf3 Nc6 Bb5 Nf6"),
+    EcoEntry::new(
+        "C68",
+        "Ruy Lopez: Exchange Variation",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Bxc6",
+    ),
+    EcoEntry::new(
+        "C70",
+        "Ruy Lopez: Morphy Defense",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4",
+    ),
+    EcoEntry::new("D00", "Queen's Pawn Game", "d4 d5"),
+    EcoEntry::new("D06", "Queen's Gambit", "d4 d5 c4"),
+    EcoEntry::new("D10", "Slav Defense", "d4 d5 c4 c6"),
+    EcoEntry::new("D20", "Queen's Gambit Accepted", "d4 d5 c4 dxc4"),
+    EcoEntry::new("D30", "Queen's Gambit Declined", "d4 d5 c4 e6"),
+    EcoEntry::new(
+        "D35",
+        "Queen's Gambit Declined: Exchange Variation",
+        "d4 d5 c4 e6 Nc3 Nf6 cxd5",
+    ),
+    EcoEntry::new("D80", "Grünfeld Defense", "d4 Nf6 c4 g6 Nc3 d5"),
+    EcoEntry::new("E00", "Catalan Opening", "d4 Nf6 c4 e6 g3"),
+    EcoEntry::new("E12", "Queen's Indian Defense", "d4 Nf6 c4 e6 Nf3 b6"),
+    EcoEntry::new("E20", "Nimzo-Indian Defense", "d4 Nf6 c4 e6 Nc3 Bb4"),
+    EcoEntry::new("E60", "King's Indian Defense", "d4 Nf6 c4 g6"),
+];
+
+impl Chessboard {
+    /// Returns the deepest opening from the ECO table that the game's moves follow, or `None`
+    /// if the game didn't start from the standard position or matches no opening.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// for san in ["e4", "e5", "Nf3", "Nc6", "Bb5"] {
+    ///     board.move_to(san).unwrap();
+    /// }
+    /// let opening = board.classify().unwrap();
+    /// assert_eq!(opening.code(), "C60");
+    /// assert_eq!(opening.name(), "Ruy Lopez");
+    /// ```
+    pub fn classify(&self) -> Option<EcoEntry> {
+        if !fen_positions_equal(self.history.first()?.before(), START_FEN) {
+            return None;
+        }
+
+        let depth = ENTRIES
+            .iter()
+            .map(|entry| entry.moves.split_whitespace().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut board = Chessboard::new();
+        let mut sans = Vec::new();
+        for chess_move in self.history.iter().take(depth) {
+            let info = chess_move.to_move_info();
+            let san = board.san_without_suffix(&info);
+            board.apply_move_info(&info, san.clone());
+            sans.push(san);
+        }
+
+        ENTRIES
+            .iter()
+            .filter(|entry| {
+                let moves: Vec<&str> = entry.moves.split_whitespace().collect();
+                moves.len() <= sans.len() && moves.iter().zip(sans.iter()).all(|(a, b)| a == b)
+            })
+            .max_by_key(|entry| entry.moves.split_whitespace().count())
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(sans: &[&str]) -> Chessboard {
+        let mut board = Chessboard::new();
+        for san in sans {
+            board.move_to(san).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    fn test_classify() {
+        let opening = play(&["e4", "e5", "Nf3", "Nc6", "Bb5"]).classify().unwrap();
+        assert_eq!(opening.code(), "C60");
+        assert_eq!(opening.name(), "Ruy Lopez");
+
+        let opening = play(&["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6"])
+            .classify()
+            .unwrap();
+        assert_eq!(opening.code(), "C70");
+
+        let opening = play(&["d4", "Nf6", "c4", "e6", "Nc3", "Bb4"])
+            .classify()
+            .unwrap();
+        assert_eq!(opening.name(), "Nimzo-Indian Defense");
+
+        let opening = play(&["e2e4", "e7e5", "Ng1f3"]).classify().unwrap();
+        assert_eq!(opening.code(), "C40");
+
+        assert_eq!(play(&["h4"]).classify(), None);
+        assert_eq!(Chessboard::new().classify(), None);
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+        board.move_to("e4").unwrap();
+        assert_eq!(board.classify(), None);
+    }
+
+    #[test]
+    fn test_eco_table() {
+        for entry in ENTRIES {
+            let mut board = Chessboard::new();
+            for san in entry.moves().split_whitespace() {
+                assert_eq!(
+                    board
+                        .play(san)
+                        .ok()
+                        .as_deref()
+                        .map(|played| played.trim_end_matches(['+', '#'])),
+                    Some(san),
+                    "{}",
+                    entry.name()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::{fen, Chessboard, FenError};
+
+/// An error returned when an EPD line can't be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpdError {
+    /// The line doesn't start with the four position fields.
+    MissingFields,
+    /// The position fields don't describe a valid position.
+    InvalidPosition(FenError),
+    /// An operation has no opcode, or a quoted operand isn't closed.
+    InvalidOperation,
+}
+
+impl std::fmt::Display for EpdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EpdError::MissingFields => write!(f, "EPD must start with four position fields"),
+            EpdError::InvalidPosition(error) => write!(f, "Invalid EPD position: {}", error),
+            EpdError::InvalidOperation => write!(f, "Invalid EPD operation"),
+        }
+    }
+}
+
+impl std::error::Error for EpdError {}
+
+pub(crate) fn parse(epd: &str) -> Result<(Chessboard, HashMap<String, String>), EpdError> {
+    let epd = epd.trim();
+    let mut fields = Vec::new();
+    let mut rest = epd;
+
+    for _ in 0..4 {
+        let (field, remainder) = match rest.split_once(char::is_whitespace) {
+            Some((field, remainder)) => (field, remainder.trim_start()),
+            None => (rest, ""),
+        };
+
+        if field.is_empty() {
+            return Err(EpdError::MissingFields);
+        }
+
+        fields.push(field);
+        rest = remainder;
+    }
+
+    let operations = parse_operations(rest)?;
+
+    let half_move = operations.get("hmvc").map_or("0", |value| value.as_str());
+    let full_move = operations.get("fmvn").map_or("1", |value| value.as_str());
+    let fen = format!("{} {} {}", fields.join(" "), half_move, full_move);
+
+    fen::validate(&fen).map_err(EpdError::InvalidPosition)?;
+
+    Ok((Chessboard::from_fen(&fen), operations))
+}
+
+fn parse_operations(operations: &str) -> Result<HashMap<String, String>, EpdError> {
+    let mut parsed = HashMap::new();
+    let mut chars = operations.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut opcode = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == ';' {
+                break;
+            }
+            opcode.push(c);
+            chars.next();
+        }
+
+        if opcode.is_empty() {
+            return Err(EpdError::InvalidOperation);
+        }
+
+        let mut operand = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                ';' => break,
+                '"' => {
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '"' {
+                            closed = true;
+                            break;
+                        }
+                        operand.push(c);
+                    }
+
+                    if !closed {
+                        return Err(EpdError::InvalidOperation);
+                    }
+                }
+                _ => operand.push(c),
+            }
+        }
+
+        parsed.insert(opcode, operand.trim().to_string());
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_epd() {
+        let (board, operations) = parse(
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+        )
+        .unwrap();
+
+        assert_eq!(
+            board.get_fen(),
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1"
+        );
+        assert_eq!(operations.get("bm").unwrap(), "Qg6");
+        assert_eq!(operations.get("id").unwrap(), "WAC.001");
+        assert_eq!(operations.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_epd_counters() {
+        let (board, operations) = parse(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - hmvc 3; fmvn 12; c0 \"a; b\";",
+        )
+        .unwrap();
+
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3 12"
+        );
+        assert_eq!(operations.get("c0").unwrap(), "a; b");
+    }
+
+    #[test]
+    fn test_parse_epd_errors() {
+        assert_eq!(
+            parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w").unwrap_err(),
+            EpdError::MissingFields
+        );
+        assert_eq!(
+            parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq -").unwrap_err(),
+            EpdError::InvalidPosition(FenError::InvalidTurn)
+        );
+        assert_eq!(
+            parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - id \"WAC").unwrap_err(),
+            EpdError::InvalidOperation
+        );
+    }
+}
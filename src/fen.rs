@@ -0,0 +1,232 @@
+use crate::{Chessboard, Square};
+
+/// An error returned when a FEN string can't be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenError {
+    /// The FEN doesn't have the six space separated fields.
+    MissingFields,
+    /// The piece placement doesn't describe eight ranks of eight squares.
+    InvalidPlacement,
+    /// The piece placement contains an unknown piece letter.
+    InvalidPiece(char),
+    /// The side to move isn't `w` or `b`.
+    InvalidTurn,
+    /// The castling field contains something other than `KQkq` or `-`.
+    InvalidCastling(char),
+    /// The en passant field isn't a square or `-`.
+    InvalidEnPassant,
+    /// The half-move clock isn't a number.
+    InvalidHalfMove,
+    /// The full-move number isn't a number.
+    InvalidFullMove,
+    /// A side doesn't have exactly one king.
+    InvalidKings,
+}
+
+impl std::fmt::Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FenError::MissingFields => write!(f, "FEN must have six fields"),
+            FenError::InvalidPlacement => write!(f, "Invalid piece placement"),
+            FenError::InvalidPiece(c) => write!(f, "Invalid piece '{}'", c),
+            FenError::InvalidTurn => write!(f, "Invalid side to move"),
+            FenError::InvalidCastling(c) => write!(f, "Invalid castling right '{}'", c),
+            FenError::InvalidEnPassant => write!(f, "Invalid en passant square"),
+            FenError::InvalidHalfMove => write!(f, "Invalid half-move clock"),
+            FenError::InvalidFullMove => write!(f, "Invalid full-move number"),
+            FenError::InvalidKings => write!(f, "Each side must have exactly one king"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// A non-fatal adjustment made while loading a FEN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenWarning {
+    /// The castling right was dropped because the king or rook isn't on its starting square.
+    CastlingRightRemoved(char),
+    /// The en passant square was dropped because no pawn could have just moved past it.
+    EnPassantRemoved(Square),
+}
+
+impl std::fmt::Display for FenWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FenWarning::CastlingRightRemoved(c) => write!(f, "Castling right '{}' removed", c),
+            FenWarning::EnPassantRemoved(square) => {
+                write!(f, "En passant square {} removed", square)
+            }
+        }
+    }
+}
+
+pub(crate) fn validate(fen: &str) -> Result<(), FenError> {
+    let parts: Vec<&str> = fen.split_whitespace().collect();
+
+    if parts.len() != 6 {
+        return Err(FenError::MissingFields);
+    }
+
+    let ranks: Vec<&str> = parts[0].split('/').collect();
+    if ranks.len() != 8 {
+        return Err(FenError::InvalidPlacement);
+    }
+
+    for rank in ranks {
+        let mut files = 0;
+        for c in rank.chars() {
+            match c {
+                '1'..='8' => files += c.to_digit(10).unwrap(),
+                'p' | 'n' | 'b' | 'r' | 'q' | 'k' | 'P' | 'N' | 'B' | 'R' | 'Q' | 'K' => files += 1,
+                _ => return Err(FenError::InvalidPiece(c)),
+            }
+        }
+
+        if files != 8 {
+            return Err(FenError::InvalidPlacement);
+        }
+    }
+
+    for king in ['K', 'k'] {
+        if parts[0].matches(king).count() != 1 {
+            return Err(FenError::InvalidKings);
+        }
+    }
+
+    if parts[1] != "w" && parts[1] != "b" {
+        return Err(FenError::InvalidTurn);
+    }
+
+    if parts[2] != "-" {
+        for c in parts[2].chars() {
+            if !matches!(c, 'K' | 'Q' | 'k' | 'q') {
+                return Err(FenError::InvalidCastling(c));
+            }
+        }
+    }
+
+    if parts[3] != "-" && Square::from(parts[3]) == Square::UNKNOWN {
+        return Err(FenError::InvalidEnPassant);
+    }
+
+    if parts[4].parse::<u32>().is_err() {
+        return Err(FenError::InvalidHalfMove);
+    }
+
+    if parts[5].parse::<u32>().is_err() {
+        return Err(FenError::InvalidFullMove);
+    }
+
+    Ok(())
+}
+
+/// Returns whether two FENs describe the same position, ignoring the move counters.
+/// The en passant square is ignored when no pawn can capture on it.
+/// # Examples
+/// ```
+/// use w_chess::fen_positions_equal;
+/// assert!(fen_positions_equal(
+///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 4 3",
+/// ));
+/// ```
+pub fn fen_positions_equal(a: &str, b: &str) -> bool {
+    match (position_key(a), position_key(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn position_key(fen: &str) -> Option<String> {
+    validate(fen).ok()?;
+
+    let parts: Vec<&str> = fen.split_whitespace().collect();
+    let en_passant = match Chessboard::from_fen(fen).can_capture_en_passant() {
+        true => parts[3],
+        false => "-",
+    };
+
+    Some(format!(
+        "{} {} {} {}",
+        parts[0], parts[1], parts[2], en_passant
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            Ok(())
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -"),
+            Err(FenError::MissingFields)
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            Err(FenError::InvalidPlacement)
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            Err(FenError::InvalidPiece('9'))
+        );
+        assert_eq!(
+            validate("rnbqkbnr/ppppxppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            Err(FenError::InvalidPiece('x'))
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1"),
+            Err(FenError::InvalidTurn)
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQxq - 0 1"),
+            Err(FenError::InvalidCastling('x'))
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1"),
+            Err(FenError::InvalidEnPassant)
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - a 1"),
+            Err(FenError::InvalidHalfMove)
+        );
+        assert_eq!(
+            validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 -1"),
+            Err(FenError::InvalidFullMove)
+        );
+        assert_eq!(
+            validate("8/8/8/8/8/8/8/8 w - - 0 1"),
+            Err(FenError::InvalidKings)
+        );
+        assert_eq!(
+            validate("4k3/8/8/8/8/8/8/K3K3 w - - 0 1"),
+            Err(FenError::InvalidKings)
+        );
+    }
+
+    #[test]
+    fn test_fen_positions_equal() {
+        assert!(fen_positions_equal(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 12 30"
+        ));
+        assert!(!fen_positions_equal(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"
+        ));
+        assert!(fen_positions_equal(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+        ));
+        assert!(!fen_positions_equal(
+            "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+        ));
+        assert!(!fen_positions_equal("invalid", "invalid"));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{pgn, Chessboard, GameError, GameResult};
+
+/// A game imported from PGN: the board after its moves, its tags and its recorded result.
+#[derive(Debug, Clone)]
+pub struct Game {
+    board: Chessboard,
+    tags: Vec<(String, String)>,
+    result: Option<GameResult>,
+}
+
+impl Game {
+    /// Returns the game described by the PGN, replaying its moves from the `FEN` tag or the
+    /// starting position. The result comes from the result token after the moves, or from the
+    /// `Result` tag when there is none.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Game, GameResult};
+    /// let game = Game::from_pgn("[Result \"1-0\"]\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0").unwrap();
+    /// assert_eq!(game.result(), Some(GameResult::WhiteWins));
+    /// assert!(game.board().is_mate());
+    /// ```
+    pub fn from_pgn(pgn: &str) -> Result<Self, GameError> {
+        let parsed = pgn::parse(pgn);
+        let board = pgn::replay(&parsed, |_, _| {})?;
+
+        let result = parsed
+            .result
+            .as_deref()
+            .or(parsed.tag("Result"))
+            .and_then(GameResult::from_token);
+
+        Ok(Self {
+            board,
+            tags: parsed.tags,
+            result,
+        })
+    }
+
+    /// Returns the board after the moves of the game.
+    pub fn board(&self) -> &Chessboard {
+        &self.board
+    }
+
+    /// Returns the value of the tag, if the game has it.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the recorded result of the game, or `None` if it is unfinished. Unlike
+    /// `Chessboard::result`, this includes results the board can't show, such as resignations.
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_pgn_resignation() {
+        let game =
+            Game::from_pgn("[Event \"Casual\"]\n[Result \"0-1\"]\n\n1. e4 e5 2. Nf3 Nc6 0-1")
+                .unwrap();
+
+        assert_eq!(game.result(), Some(GameResult::BlackWins));
+        assert_eq!(game.board().result(), None);
+        assert_eq!(game.board().history.len(), 4);
+        assert_eq!(game.tag("Event"), Some("Casual"));
+    }
+
+    #[test]
+    fn test_from_pgn_result() {
+        let game = Game::from_pgn("1. d4 d5 *").unwrap();
+        assert_eq!(game.result(), None);
+
+        let game = Game::from_pgn("[Result \"1/2-1/2\"]\n1. d4 d5").unwrap();
+        assert_eq!(game.result(), Some(GameResult::Draw));
+
+        assert_eq!(
+            Game::from_pgn("1. e4 e5 2. Ke3 *").unwrap_err(),
+            GameError::IllegalMove(2)
+        );
+    }
+}
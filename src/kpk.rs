@@ -0,0 +1,187 @@
+use std::sync::OnceLock;
+
+use crate::{FILE_A, FILE_H};
+
+// A position is indexed by the side to move, both kings and the pawn. The strong side always
+// has a white pawn on files a to d and ranks 2 to 7, which is 24 squares.
+const SIZE: usize = 2 * 64 * 64 * 24;
+
+const INVALID: u8 = 0;
+const UNKNOWN: u8 = 1;
+const DRAW: u8 = 2;
+const WIN: u8 = 4;
+
+static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// Returns whether the side with the pawn wins, given the squares from its point of view: its
+/// pawn is white and on files a to d. Returns `None` for positions that can't occur.
+pub(crate) fn probe(
+    strong_to_move: bool,
+    strong_king: usize,
+    weak_king: usize,
+    pawn: usize,
+) -> Option<bool> {
+    match TABLE.get_or_init(generate)[index(strong_to_move, strong_king, weak_king, pawn)] {
+        INVALID => None,
+        result => Some(result == WIN),
+    }
+}
+
+fn index(strong_to_move: bool, strong_king: usize, weak_king: usize, pawn: usize) -> usize {
+    let pawn = (pawn % 8) * 6 + (pawn / 8 - 1);
+    ((strong_to_move as usize * 64 + strong_king) * 64 + weak_king) * 24 + pawn
+}
+
+fn king_attacks(square: usize) -> u64 {
+    let square: u64 = 1 << square;
+    let sides = square | (square & !FILE_A) >> 1 | (square & !FILE_H) << 1;
+    (sides | sides << 8 | sides >> 8) & !square
+}
+
+fn pawn_attacks(square: usize) -> u64 {
+    let square: u64 = 1 << square;
+    (square & !FILE_A) << 7 | (square & !FILE_H) << 9
+}
+
+fn squares(bitboard: u64) -> impl Iterator<Item = usize> {
+    (0..64).filter(move |&square| bitboard & (1 << square) != 0)
+}
+
+fn initial(strong_to_move: bool, strong_king: usize, weak_king: usize, pawn: usize) -> u8 {
+    let weak_king_bit: u64 = 1 << weak_king;
+    let pawn_bit: u64 = 1 << pawn;
+
+    if strong_king == weak_king
+        || strong_king == pawn
+        || weak_king == pawn
+        || king_attacks(strong_king) & weak_king_bit != 0
+        || (strong_to_move && pawn_attacks(pawn) & weak_king_bit != 0)
+    {
+        return INVALID;
+    }
+
+    if strong_to_move && pawn / 8 == 6 {
+        let promotion = pawn + 8;
+        let promotion_bit: u64 = 1 << promotion;
+
+        if promotion != strong_king
+            && promotion != weak_king
+            && (king_attacks(weak_king) & promotion_bit == 0
+                || king_attacks(strong_king) & promotion_bit != 0)
+        {
+            return WIN;
+        }
+    }
+
+    if !strong_to_move {
+        let escapes = king_attacks(weak_king) & !(king_attacks(strong_king) | pawn_attacks(pawn));
+        let captures = king_attacks(weak_king) & pawn_bit & !king_attacks(strong_king);
+
+        if escapes == 0 || captures != 0 {
+            return DRAW;
+        }
+    }
+
+    UNKNOWN
+}
+
+fn generate() -> Vec<u8> {
+    let mut table = vec![INVALID; SIZE];
+    let mut positions = Vec::new();
+
+    for strong_to_move in [false, true] {
+        for strong_king in 0..64 {
+            for weak_king in 0..64 {
+                for file in 0..4 {
+                    for rank in 1..7 {
+                        let pawn = rank * 8 + file;
+                        let result = initial(strong_to_move, strong_king, weak_king, pawn);
+                        table[index(strong_to_move, strong_king, weak_king, pawn)] = result;
+
+                        if result == UNKNOWN {
+                            positions.push((strong_to_move, strong_king, weak_king, pawn));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for &(strong_to_move, strong_king, weak_king, pawn) in positions.iter() {
+            let position = index(strong_to_move, strong_king, weak_king, pawn);
+            if table[position] != UNKNOWN {
+                continue;
+            }
+
+            let result = classify(&table, strong_to_move, strong_king, weak_king, pawn);
+            if result != UNKNOWN {
+                table[position] = result;
+                changed = true;
+            }
+        }
+    }
+
+    for result in table.iter_mut() {
+        if *result == UNKNOWN {
+            *result = DRAW;
+        }
+    }
+
+    table
+}
+
+fn classify(
+    table: &[u8],
+    strong_to_move: bool,
+    strong_king: usize,
+    weak_king: usize,
+    pawn: usize,
+) -> u8 {
+    let mut results = 0;
+
+    match strong_to_move {
+        true => {
+            for square in squares(king_attacks(strong_king)) {
+                results |= table[index(false, square, weak_king, pawn)];
+            }
+
+            // Promotions from the seventh rank are already classified as wins when possible.
+            if pawn / 8 < 6 {
+                let push = pawn + 8;
+                if push != strong_king && push != weak_king {
+                    results |= table[index(false, strong_king, weak_king, push)];
+
+                    let double_push = push + 8;
+                    if pawn / 8 == 1 && double_push != strong_king && double_push != weak_king {
+                        results |= table[index(false, strong_king, weak_king, double_push)];
+                    }
+                }
+            }
+
+            if results & WIN != 0 {
+                WIN
+            } else if results & UNKNOWN != 0 {
+                UNKNOWN
+            } else {
+                DRAW
+            }
+        }
+        false => {
+            for square in squares(king_attacks(weak_king)) {
+                results |= table[index(true, strong_king, square, pawn)];
+            }
+
+            if results & DRAW != 0 {
+                DRAW
+            } else if results & UNKNOWN != 0 {
+                UNKNOWN
+            } else {
+                WIN
+            }
+        }
+    }
+}
@@ -1,23 +1,49 @@
 mod chess_move;
+mod color;
+mod debug;
+mod eco;
+mod epd;
+mod fen;
+mod game;
+mod game_result;
+mod kpk;
+mod pgn;
 mod piece;
+mod position;
+mod provider;
+#[cfg(feature = "rand")]
+mod random;
 mod square;
-
-pub use chess_move::ChessMove;
-use chess_move::{CastlingType, SanMove};
+#[cfg(feature = "svg")]
+mod svg;
+mod zobrist;
+
+pub use chess_move::{CastlingType, ChessMove, MoveError, MoveInfo, SanMove, SanParseError};
+pub use color::Color;
+pub use debug::{format_mask, DebugMasks};
+pub use eco::EcoEntry;
+pub use epd::EpdError;
+pub use fen::{fen_positions_equal, FenError, FenWarning};
+pub use game::Game;
+pub use game_result::{GameResult, GameStatus, Termination};
+pub use pgn::{validate_game, GameError};
 pub use piece::Piece;
+pub use position::Position;
+pub use provider::MoveProvider;
 use std::collections::HashMap;
 
 use square::{
     BLACK_KING_SIDE_CASTLE, BLACK_KING_SIDE_CASTLE_SQUARE, BLACK_QUEEN_SIDE_CASTLE,
-    BLACK_QUEEN_SIDE_CASTLE_SQUARE, FILE_A, FILE_B, FILE_C, FILE_D, FILE_E, FILE_F, FILE_G, FILE_H,
-    RANK_1, RANK_2, RANK_3, RANK_4, RANK_5, RANK_6, RANK_7, RANK_8, START_FEN,
-    WHITE_KING_SIDE_CASTLE, WHITE_KING_SIDE_CASTLE_SQUARE, WHITE_QUEEN_SIDE_CASTLE,
-    WHITE_QUEEN_SIDE_CASTLE_SQUARE,
+    BLACK_QUEEN_SIDE_CASTLE_SQUARE, DARK_SQUARES, START_FEN, WHITE_KING_SIDE_CASTLE,
+    WHITE_KING_SIDE_CASTLE_SQUARE, WHITE_QUEEN_SIDE_CASTLE, WHITE_QUEEN_SIDE_CASTLE_SQUARE,
 };
 
-pub use square::Square;
+pub use square::{
+    Square, FILE_A, FILE_B, FILE_C, FILE_D, FILE_E, FILE_F, FILE_G, FILE_H, RANK_1, RANK_2, RANK_3,
+    RANK_4, RANK_5, RANK_6, RANK_7, RANK_8,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Chessboard {
     white: u64,
     black: u64,
@@ -35,43 +61,234 @@ pub struct Chessboard {
     half_move: u32,
     full_move: u32,
 
-    board_repetitions: HashMap<String, usize>,
+    zobrist: u64,
+    position_keys: Vec<u64>,
+    redo: Vec<ChessMove>,
+    auto_queen: bool,
 
     /// The history of the game in SAN format.
     pub history: Vec<ChessMove>,
 }
 
+/// A checkpoint of the game state that isn't stored in the bitboards: the side to move,
+/// castling rights, en passant square and move counters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateSnapshot {
+    turn: bool,
+    castle_rights: [bool; 4],
+    en_passant_square: Option<u64>,
+    half_move: u32,
+    full_move: u32,
+}
+
+/// The pieces giving check to the side to move, and the squares a piece could interpose on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckInfo {
+    /// A bitboard of the enemy pieces attacking the king.
+    pub checkers: u64,
+    /// A bitboard of the squares between a single sliding checker and the king. It is empty for
+    /// knight, pawn and double checks, which can't be blocked.
+    pub block_squares: u64,
+}
+
 impl Chessboard {
     /// Returns a chessboard with the starting position.
     /// # Examples
     /// ```
     /// use w_chess::Chessboard;
     /// let mut board = Chessboard::new();
-    /// board.move_to("e4");
+    /// board.move_to("e4").unwrap();
     /// ```
     pub fn new() -> Self {
         let mut board = Self::load_fen(START_FEN);
 
         board.generate_legal_moves();
+        board.zobrist = board.zobrist_key();
+        board.position_keys.push(board.zobrist);
 
         board
     }
 
     /// Returns a chessboard with the position from the FEN string.
+    /// Positions that occurred before the FEN aren't known, so repetitions only count from here;
+    /// use `with_repetition_seed` to resume a game with its earlier positions.
     /// # Examples
     /// ```
     /// use w_chess::Chessboard;
     /// let mut board = Chessboard::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
-    /// board.move_to("e4");
+    /// board.move_to("e4").unwrap();
     /// ```
     pub fn from_fen(fen: &str) -> Self {
         let mut board = Self::load_fen(fen);
 
         board.generate_legal_moves();
+        board.zobrist = board.zobrist_key();
+        board.position_keys.push(board.zobrist);
+
+        board
+    }
+
+    /// Returns a chessboard with the position from the FEN string, counting the prior FENs as
+    /// positions that already occurred in the game for repetition detection. Only as many prior
+    /// positions as the FEN's half-move clock allows are considered.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let fen = "4k3/8/8/8/8/8/8/4K3 w - - 8 5".to_string();
+    /// let board = Chessboard::with_repetition_seed(&fen, &[fen.clone(), fen.clone()]);
+    /// assert!(board.is_threefold_repetition());
+    /// ```
+    pub fn with_repetition_seed(fen: &str, prior: &[String]) -> Self {
+        let mut board = Self::from_fen(fen);
+
+        board.position_keys = prior
+            .iter()
+            .map(|fen| Self::from_fen(fen).zobrist)
+            .collect();
+        board.position_keys.push(board.zobrist);
+
+        board
+    }
+
+    /// Plays the moves of a game in PGN, calling `f` with the board and the move after each ply,
+    /// and returns the final board. The game starts from its `FEN` tag when it has one.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut plies = 0;
+    /// let board = Chessboard::replay_pgn("1. e4 e5 2. Nf3 *", |_, _| plies += 1).unwrap();
+    /// assert_eq!(plies, 3);
+    /// assert_eq!(board.history.len(), 3);
+    /// ```
+    pub fn replay_pgn<F: FnMut(&Chessboard, &ChessMove)>(
+        pgn: &str,
+        f: F,
+    ) -> Result<Chessboard, GameError> {
+        pgn::replay(&pgn::parse(pgn), f)
+    }
+
+    /// Returns a chessboard with the position from the FEN string after playing the moves.
+    /// Moves that can't be played are skipped.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen_with_moves("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &["e4", "e5"]);
+    /// assert_eq!(board.history.len(), 2);
+    /// ```
+    pub fn from_fen_with_moves(fen: &str, moves: &[&str]) -> Self {
+        let mut board = Self::from_fen(fen);
+
+        for san in moves {
+            let _ = board.move_to(san);
+        }
 
         board
     }
 
+    /// Returns a chessboard with the position from an EPD line, along with its operations keyed by
+    /// opcode. The move counters come from the `hmvc` and `fmvn` operations when present.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let (board, operations) = Chessboard::from_epd(
+    ///     "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+    /// ).unwrap();
+    /// assert_eq!(operations["id"], "WAC.001");
+    /// ```
+    pub fn from_epd(epd: &str) -> Result<(Self, HashMap<String, String>), EpdError> {
+        epd::parse(epd)
+    }
+
+    /// Returns a chessboard with the position from the FEN string, along with the adjustments
+    /// made while loading it, such as castling rights that don't match the position.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, FenWarning};
+    /// let (board, warnings) = Chessboard::from_fen_checked("4k3/8/8/8/8/8/8/4K2R w KQ - 0 1").unwrap();
+    /// assert_eq!(warnings, vec![FenWarning::CastlingRightRemoved('Q')]);
+    /// assert_eq!(board.get_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+    /// ```
+    pub fn from_fen_checked(fen: &str) -> Result<(Self, Vec<FenWarning>), FenError> {
+        fen::validate(fen)?;
+
+        let mut board = Self::load_fen(fen);
+        let warnings = board.sanitize();
+
+        board.generate_legal_moves();
+        board.zobrist = board.zobrist_key();
+        board.position_keys.push(board.zobrist);
+
+        Ok((board, warnings))
+    }
+
+    /// Returns a chessboard with the position from the FEN string, or an error if the FEN is
+    /// malformed or a side doesn't have exactly one king.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, FenError};
+    /// assert!(Chessboard::try_from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").is_ok());
+    /// assert_eq!(
+    ///     Chessboard::try_from_fen("8/8/8/8/8/8/8/8 w - - 0 1").unwrap_err(),
+    ///     FenError::InvalidKings
+    /// );
+    /// ```
+    pub fn try_from_fen(fen: &str) -> Result<Self, FenError> {
+        Self::from_fen_checked(fen).map(|(board, _)| board)
+    }
+
+    fn sanitize(&mut self) -> Vec<FenWarning> {
+        let mut warnings = Vec::new();
+
+        let castles = [
+            ('K', self.white, Square::E1, Square::H1),
+            ('Q', self.white, Square::E1, Square::A1),
+            ('k', self.black, Square::E8, Square::H8),
+            ('q', self.black, Square::E8, Square::A8),
+        ];
+
+        for (index, (c, color, king, rook)) in castles.into_iter().enumerate() {
+            let king: u64 = king.into();
+            let rook: u64 = rook.into();
+
+            if self.castle_rights[index]
+                && (self.pieces[Piece::KING as usize] & color & king == 0
+                    || self.pieces[Piece::ROOK as usize] & color & rook == 0)
+            {
+                self.castle_rights[index] = false;
+                warnings.push(FenWarning::CastlingRightRemoved(c));
+            }
+        }
+
+        if let Some(en_passant_square) = self.en_passant_square {
+            let (rank, pawn, origin, enemy) = match self.turn {
+                true => (
+                    RANK_6,
+                    en_passant_square >> 8,
+                    en_passant_square << 8,
+                    self.black,
+                ),
+                false => (
+                    RANK_3,
+                    en_passant_square << 8,
+                    en_passant_square >> 8,
+                    self.white,
+                ),
+            };
+
+            if en_passant_square & rank == 0
+                || (en_passant_square | origin) & self.all() != 0
+                || self.pieces[Piece::PAWN as usize] & enemy & pawn == 0
+            {
+                self.en_passant_square = None;
+                warnings.push(FenWarning::EnPassantRemoved(Square::from(
+                    en_passant_square,
+                )));
+            }
+        }
+
+        warnings
+    }
+
     fn get_color(&self, color: bool) -> u64 {
         match color {
             true => self.white,
@@ -88,14 +305,8 @@ impl Chessboard {
 
         match color {
             true => {
-                let mut mask = 0;
-                if square << 7 & self.black != 0 {
-                    mask |= square << 7;
-                }
-
-                if square << 9 & self.black != 0 {
-                    mask |= square << 9;
-                }
+                let attacks = self.generate_pawn_attacks(square, color);
+                let mut mask = attacks & self.black;
 
                 if square << 8 & self.all() == 0 {
                     mask |= square << 8;
@@ -105,23 +316,15 @@ impl Chessboard {
                 }
 
                 if let Some(en_passant_square) = self.en_passant_square {
-                    if square << 7 == en_passant_square || square << 9 == en_passant_square {
-                        mask |= en_passant_square;
-                    }
+                    mask |= attacks & en_passant_square;
                 }
 
                 mask
             }
             false => {
-                let mut mask = 0;
+                let attacks = self.generate_pawn_attacks(square, color);
+                let mut mask = attacks & self.white;
 
-                if square >> 7 & self.white != 0 {
-                    mask |= square >> 7;
-                }
-
-                if square >> 9 & self.white != 0 {
-                    mask |= square >> 9;
-                }
                 if square >> 8 & self.all() == 0 {
                     mask |= square >> 8;
                     if square & RANK_7 != 0 && square >> 16 & self.all() == 0 {
@@ -130,9 +333,7 @@ impl Chessboard {
                 }
 
                 if let Some(en_passant_square) = self.en_passant_square {
-                    if square >> 7 == en_passant_square || square >> 9 == en_passant_square {
-                        mask |= en_passant_square;
-                    }
+                    mask |= attacks & en_passant_square;
                 }
 
                 mask
@@ -279,27 +480,27 @@ impl Chessboard {
             mask |= square >> 8;
         }
 
-        if square & FILE_A == 0 {
+        if square & FILE_H == 0 {
             mask |= square << 1;
         }
 
-        if square & FILE_H == 0 {
+        if square & FILE_A == 0 {
             mask |= square >> 1;
         }
 
-        if square & (RANK_8 | FILE_A) == 0 {
+        if square & (RANK_8 | FILE_H) == 0 {
             mask |= square << 9;
         }
 
-        if square & (RANK_8 | FILE_H) == 0 {
+        if square & (RANK_8 | FILE_A) == 0 {
             mask |= square << 7;
         }
 
-        if square & (RANK_1 | FILE_A) == 0 {
+        if square & (RANK_1 | FILE_H) == 0 {
             mask |= square >> 7;
         }
 
-        if square & (RANK_1 | FILE_H) == 0 {
+        if square & (RANK_1 | FILE_A) == 0 {
             mask |= square >> 9;
         }
 
@@ -310,9 +511,14 @@ impl Chessboard {
         self.generate_rook_moves(square, board) | self.generate_bishop_moves(square, board)
     }
 
+    fn generate_pawn_attacks(&self, square: u64, color: bool) -> u64 {
+        match color {
+            true => (square & !FILE_A) << 7 | (square & !FILE_H) << 9,
+            false => (square & !FILE_H) >> 7 | (square & !FILE_A) >> 9,
+        }
+    }
+
     fn generate_pseudo_legal_moves(&mut self) {
-        self.static_white_attack_mask = 0;
-        self.static_black_attack_mask = 0;
         self.dynamic_piece_squares.clear();
 
         for i in 0..64 {
@@ -323,16 +529,6 @@ impl Chessboard {
                 let piece = self.get_piece(square);
                 match piece {
                     Piece::PAWN => {
-                        match color {
-                            true => {
-                                self.static_white_attack_mask |= square << 7;
-                                self.static_white_attack_mask |= square << 9;
-                            }
-                            false => {
-                                self.static_black_attack_mask |= square >> 7;
-                                self.static_black_attack_mask |= square >> 9;
-                            }
-                        }
                         self.pseudo_legal_moves
                             .insert(square, self.generate_pawn_moves(square));
                     }
@@ -351,15 +547,6 @@ impl Chessboard {
                     }
                     Piece::KNIGHT => {
                         let mask = self.generate_knight_moves(square);
-
-                        match color {
-                            true => {
-                                self.static_white_attack_mask |= mask;
-                            }
-                            false => {
-                                self.static_black_attack_mask |= mask;
-                            }
-                        }
                         self.pseudo_legal_moves
                             .insert(square, mask & !self.get_color(color));
                     }
@@ -367,8 +554,6 @@ impl Chessboard {
                         let mut mask = self.generate_king_moves(square);
                         match color {
                             true => {
-                                self.static_white_attack_mask |= mask;
-
                                 if self.turn {
                                     if self.castle_rights[0]
                                         && self.all() & WHITE_KING_SIDE_CASTLE == 0
@@ -376,16 +561,18 @@ impl Chessboard {
                                         mask |= 1 << Square::G1 as u64;
                                     }
 
+                                    // The b1 square has to be empty for the rook to pass, but
+                                    // the king never crosses it.
                                     if self.castle_rights[1]
-                                        && self.all() & WHITE_QUEEN_SIDE_CASTLE == 0
+                                        && self.all()
+                                            & (WHITE_QUEEN_SIDE_CASTLE | u64::from(Square::B1))
+                                            == 0
                                     {
                                         mask |= 1 << Square::C1 as u64;
                                     }
                                 }
                             }
                             false => {
-                                self.static_black_attack_mask |= mask;
-
                                 if !self.turn {
                                     if self.castle_rights[2]
                                         && self.all() & BLACK_KING_SIDE_CASTLE == 0
@@ -394,7 +581,9 @@ impl Chessboard {
                                     }
 
                                     if self.castle_rights[3]
-                                        && self.all() & BLACK_QUEEN_SIDE_CASTLE == 0
+                                        && self.all()
+                                            & (BLACK_QUEEN_SIDE_CASTLE | u64::from(Square::B8))
+                                            == 0
                                     {
                                         mask |= 1 << Square::C8 as u64;
                                     }
@@ -415,11 +604,83 @@ impl Chessboard {
         }
     }
 
+    fn generate_static_attack(&self, piece: Piece, color: bool, square: u64) -> u64 {
+        match piece {
+            Piece::PAWN => self.generate_pawn_attacks(square, color),
+            Piece::KNIGHT => self.generate_knight_moves(square),
+            Piece::KING => self.generate_king_moves(square),
+            _ => 0,
+        }
+    }
+
+    fn generate_static_attack_mask(&self, color: bool) -> u64 {
+        let mut mask = 0;
+
+        for piece in [Piece::PAWN, Piece::KNIGHT, Piece::KING] {
+            for square in Self::get_squares(self.pieces[piece as usize] & self.get_color(color)) {
+                mask |= self.generate_static_attack(piece, color, square);
+            }
+        }
+
+        mask
+    }
+
+    fn generate_static_attack_masks(&mut self) {
+        self.static_white_attack_mask = self.generate_static_attack_mask(true);
+        self.static_black_attack_mask = self.generate_static_attack_mask(false);
+    }
+
+    fn is_statically_attacked(&self, square: u64, color: bool) -> bool {
+        let pieces = self.get_color(color);
+
+        self.generate_pawn_attacks(square, !color) & self.pieces[Piece::PAWN as usize] & pieces != 0
+            || self.generate_knight_moves(square) & self.pieces[Piece::KNIGHT as usize] & pieces
+                != 0
+            || self.generate_king_moves(square) & self.pieces[Piece::KING as usize] & pieces != 0
+    }
+
+    /// Updates the pawn, knight and king attack masks after a move, given the bitboards from
+    /// before it. Only the attacks of the pieces that left or arrived on a square are looked at:
+    /// the squares a departed piece attacked are checked again against the remaining pieces.
+    fn update_static_attack_masks(&mut self, white: u64, black: u64, pieces: [u64; 6]) {
+        for (color, before) in [(true, white), (false, black)] {
+            let after = self.get_color(color);
+            let mut mask = match color {
+                true => self.static_white_attack_mask,
+                false => self.static_black_attack_mask,
+            };
+            let mut removed = 0;
+
+            for piece in [Piece::PAWN, Piece::KNIGHT, Piece::KING] {
+                let before = pieces[piece as usize] & before;
+                let after = self.pieces[piece as usize] & after;
+
+                for square in Self::get_squares(before & !after) {
+                    removed |= self.generate_static_attack(piece, color, square);
+                }
+                for square in Self::get_squares(after & !before) {
+                    mask |= self.generate_static_attack(piece, color, square);
+                }
+            }
+
+            for square in Self::get_squares(removed) {
+                if !self.is_statically_attacked(square, color) {
+                    mask &= !square;
+                }
+            }
+
+            match color {
+                true => self.static_white_attack_mask = mask,
+                false => self.static_black_attack_mask = mask,
+            }
+        }
+    }
+
     fn generate_legal_moves(&mut self) {
         self.generate_pseudo_legal_moves();
 
         for (&square, &moves) in self.pseudo_legal_moves.iter() {
-            let current_square: u64 = square.into();
+            let current_square: u64 = square;
             let color = self.white & current_square != 0;
             let piece = self.get_piece(current_square);
             if current_square & self.get_color(self.turn) == 0 {
@@ -439,12 +700,17 @@ impl Chessboard {
                         true => {
                             let g1: u64 = Square::G1.into();
                             let c1: u64 = Square::C1.into();
+                            // The king can't castle out of, through or into check.
                             if potential_square & g1 != 0 {
-                                if enemy_attack_mask & WHITE_KING_SIDE_CASTLE == 0 {
+                                if enemy_attack_mask & (WHITE_KING_SIDE_CASTLE | current_square)
+                                    == 0
+                                {
                                     legal_moves |= potential_square;
                                 }
                             } else if potential_square & c1 != 0 {
-                                if enemy_attack_mask & WHITE_QUEEN_SIDE_CASTLE == 0 {
+                                if enemy_attack_mask & (WHITE_QUEEN_SIDE_CASTLE | current_square)
+                                    == 0
+                                {
                                     legal_moves |= potential_square;
                                 }
                             } else {
@@ -457,11 +723,15 @@ impl Chessboard {
                             let g8: u64 = Square::G8.into();
                             let c8: u64 = Square::C8.into();
                             if potential_square & g8 != 0 {
-                                if enemy_attack_mask & BLACK_KING_SIDE_CASTLE_SQUARE == 0 {
+                                if enemy_attack_mask & (BLACK_KING_SIDE_CASTLE | current_square)
+                                    == 0
+                                {
                                     legal_moves |= potential_square;
                                 }
                             } else if potential_square & c8 != 0 {
-                                if enemy_attack_mask & BLACK_QUEEN_SIDE_CASTLE_SQUARE == 0 {
+                                if enemy_attack_mask & (BLACK_QUEEN_SIDE_CASTLE | current_square)
+                                    == 0
+                                {
                                     legal_moves |= potential_square;
                                 }
                             } else {
@@ -473,17 +743,22 @@ impl Chessboard {
                     },
                     _ => {
                         let king = self.pieces[Piece::KING as usize] & self.get_color(color);
-                        match color {
-                            true => {
-                                if enemy_attack_mask & king == 0 {
-                                    legal_moves |= potential_square;
-                                }
-                            }
-                            false => {
-                                if enemy_attack_mask & king == 0 {
-                                    legal_moves |= potential_square;
-                                }
-                            }
+                        let captured_square =
+                            self.get_captured_square(current_square, potential_square);
+
+                        // A captured piece no longer attacks anything, so its contribution to the
+                        // cached masks has to be dropped.
+                        let enemy_attack_mask = match captured_square {
+                            0 => enemy_attack_mask,
+                            _ => self.get_attack_mask_without(
+                                color,
+                                board & !captured_square,
+                                captured_square,
+                            ),
+                        };
+
+                        if enemy_attack_mask & king == 0 {
+                            legal_moves |= potential_square;
                         }
                     }
                 };
@@ -524,8 +799,6 @@ impl Chessboard {
         let mut white = 0;
         let mut black = 0;
         let mut pieces = [0, 0, 0, 0, 0, 0];
-        let static_white_attack_mask = 0;
-        let static_black_attack_mask = 0;
         let mut castle_rights = [false; 4];
         let mut turn = true;
         let mut en_passant_square = None;
@@ -547,7 +820,7 @@ impl Chessboard {
                     }
                 }
                 if parts[3] != "-" {
-                    en_passant_square = Some(Square::from(parts[3]) as u64);
+                    en_passant_square = Some(Square::from(parts[3]).into());
                 }
 
                 half_move = parts[4].parse().unwrap();
@@ -557,10 +830,10 @@ impl Chessboard {
                 if file > 7 {
                     break;
                 }
-                if c.is_digit(10) {
+                if c.is_ascii_digit() {
                     file += c.to_digit(10).unwrap() as u64;
                 } else {
-                    let square = 1 << 56 - (index as u64) * 8 + file;
+                    let square = 1 << (56 - index as u64 * 8 + file);
                     let color = c.is_uppercase();
                     match c.to_ascii_lowercase() {
                         'p' => {
@@ -612,11 +885,11 @@ impl Chessboard {
             }
         }
 
-        Self {
+        let mut board = Self {
             white,
-            static_white_attack_mask,
+            static_white_attack_mask: 0,
             black,
-            static_black_attack_mask,
+            static_black_attack_mask: 0,
             pieces,
             legal_moves: HashMap::new(),
             pseudo_legal_moves: HashMap::new(),
@@ -627,1006 +900,4500 @@ impl Chessboard {
             half_move,
             full_move,
             history: Vec::new(),
-            board_repetitions: HashMap::from([(
-                fen.split_whitespace().next().unwrap().to_string(),
-                1,
-            )]),
-        }
+            zobrist: 0,
+            position_keys: Vec::new(),
+            redo: Vec::new(),
+            auto_queen: true,
+        };
+
+        board.generate_static_attack_masks();
+        board
     }
 
-    /// Returns the FEN string of the current position.
+    /// Returns a chessboard with the position, without going through a FEN string.
     /// # Examples
     /// ```
-    /// use w_chess::Chessboard;
-    /// let board = Chessboard::new();
-    /// assert_eq!(board.get_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    /// use w_chess::{Chessboard, Color, Piece, Position};
+    /// let mut position = Position::default();
+    /// position.board[0][4] = Some((Piece::KING, Color::White));
+    /// position.board[7][4] = Some((Piece::KING, Color::Black));
+    /// let board = Chessboard::from_position(&position);
+    /// assert_eq!(board.get_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
     /// ```
-    pub fn get_fen(&self) -> String {
-        let mut fen = String::new();
-        for rank in 0..8 {
-            let mut empty = 0;
-            for file in 0..8 {
-                let square = 1 << 56 - rank as u64 * 8 + file;
-                let color = self.white & square != 0;
-                match self.get_piece(square) {
-                    Piece::PAWN => {
-                        if empty > 0 {
-                            fen.push_str(&empty.to_string());
-                            empty = 0;
-                        }
-                        match color {
-                            true => fen.push('P'),
-                            false => fen.push('p'),
-                        }
-                    }
-                    Piece::KNIGHT => {
-                        if empty > 0 {
-                            fen.push_str(&empty.to_string());
-                            empty = 0;
-                        }
-                        match color {
-                            true => fen.push('N'),
-                            false => fen.push('n'),
-                        }
-                    }
-                    Piece::BISHOP => {
-                        if empty > 0 {
-                            fen.push_str(&empty.to_string());
-                            empty = 0;
-                        }
-                        match color {
-                            true => fen.push('B'),
-                            false => fen.push('b'),
-                        }
-                    }
-                    Piece::ROOK => {
-                        if empty > 0 {
-                            fen.push_str(&empty.to_string());
-                            empty = 0;
-                        }
-                        match color {
-                            true => fen.push('R'),
-                            false => fen.push('r'),
-                        }
-                    }
-                    Piece::QUEEN => {
-                        if empty > 0 {
-                            fen.push_str(&empty.to_string());
-                            empty = 0;
-                        }
-                        match color {
-                            true => fen.push('Q'),
-                            false => fen.push('q'),
-                        }
-                    }
-                    Piece::KING => {
-                        if empty > 0 {
-                            fen.push_str(&empty.to_string());
-                            empty = 0;
-                        }
-                        match color {
-                            true => fen.push('K'),
-                            false => fen.push('k'),
-                        }
+    pub fn from_position(position: &Position) -> Self {
+        let mut white = 0;
+        let mut black = 0;
+        let mut pieces = [0; 6];
+
+        for (rank, row) in position.board.iter().enumerate() {
+            for (file, &occupant) in row.iter().enumerate() {
+                if let Some((piece, color)) = occupant {
+                    if piece == Piece::UNKNOWN {
+                        continue;
                     }
-                    _ => {
-                        empty += 1;
+
+                    let square: u64 = 1 << (rank * 8 + file);
+                    pieces[piece as usize] |= square;
+                    match color {
+                        Color::White => white |= square,
+                        Color::Black => black |= square,
                     }
                 }
             }
-            if empty > 0 {
-                fen.push_str(&empty.to_string());
-            }
-            if rank < 7 {
-                fen.push('/');
-            }
         }
 
-        let turn = if self.turn { "w" } else { "b" };
+        let mut board = Self {
+            white,
+            static_white_attack_mask: 0,
+            black,
+            static_black_attack_mask: 0,
+            pieces,
+            legal_moves: HashMap::new(),
+            pseudo_legal_moves: HashMap::new(),
+            dynamic_piece_squares: Vec::new(),
+            castle_rights: position.castling,
+            turn: position.turn.into(),
+            en_passant_square: position.en_passant.map(|square| square.into()),
+            half_move: position.halfmove,
+            full_move: position.fullmove,
+            history: Vec::new(),
+            zobrist: 0,
+            position_keys: Vec::new(),
+            redo: Vec::new(),
+            auto_queen: true,
+        };
 
-        let mut castle_rights = String::new();
+        board.generate_static_attack_masks();
+        board.generate_legal_moves();
+        board.zobrist = board.zobrist_key();
+        board.position_keys.push(board.zobrist);
 
-        if self.castle_rights[0] {
-            castle_rights.push('K');
-        }
-        if self.castle_rights[1] {
-            castle_rights.push('Q');
-        }
-        if self.castle_rights[2] {
-            castle_rights.push('k');
-        }
-        if self.castle_rights[3] {
-            castle_rights.push('q');
-        }
+        board
+    }
 
-        if castle_rights.is_empty() {
-            castle_rights = "-".to_string();
-        }
+    /// Returns the pieces on the board indexed by `[rank][file]`, so `[0][0]` is a1.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Color, Piece};
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.to_array()[0][4], Some((Piece::KING, Color::White)));
+    /// ```
+    pub fn to_array(&self) -> [[Option<(Piece, Color)>; 8]; 8] {
+        let mut array = [[None; 8]; 8];
 
-        let en_passant_square = match self.en_passant_square {
-            Some(square) => Square::from(square).to_string(),
-            None => "-".to_string(),
-        };
+        for (index, occupant) in array.iter_mut().flatten().enumerate() {
+            let square: u64 = 1 << index;
+            if self.all() & square != 0 {
+                *occupant = Some((
+                    self.get_piece(square),
+                    Color::from(self.white & square != 0),
+                ));
+            }
+        }
 
-        format!(
-            "{} {} {} {} {} {}",
-            fen, turn, castle_rights, en_passant_square, self.half_move, self.full_move
-        )
+        array
     }
 
-    /// Returns if the current position is checked.
+    /// Returns the piece on the square and whether it is white, or `None` if the square is empty.
     /// # Examples
     /// ```
-    /// use w_chess::Chessboard;
+    /// use w_chess::{Chessboard, Piece, Square};
     /// let board = Chessboard::new();
-    /// assert_eq!(board.is_checked(), false);
+    /// assert_eq!(board.piece_at(Square::G8), Some((Piece::KNIGHT, false)));
+    /// assert_eq!(board.piece_at(Square::E4), None);
     /// ```
-    pub fn is_checked(&self) -> bool {
-        let king = self.pieces[Piece::KING as usize] & self.get_color(self.turn);
-        let enemy_attack_mask = self.get_attack_mask(self.turn, self.all());
+    pub fn piece_at(&self, square: Square) -> Option<(Piece, bool)> {
+        let square: u64 = square.into();
 
-        enemy_attack_mask & king != 0
+        match self.get_piece(square) {
+            Piece::UNKNOWN => None,
+            piece => Some((piece, self.white & square != 0)),
+        }
     }
 
-    /// Returns if the current position is a checkmate.
+    /// Returns the squares occupied by white pieces.
     /// # Examples
     /// ```
     /// use w_chess::Chessboard;
     /// let board = Chessboard::new();
-    /// assert_eq!(board.is_mate(), false);
+    /// assert_eq!(board.white_pieces(), 0xFFFF);
     /// ```
-    pub fn is_mate(&self) -> bool {
-        self.is_checked() && !self.has_moves()
+    pub fn white_pieces(&self) -> u64 {
+        self.white
     }
 
-    /// Returns if the current position is a stalemate.
+    /// Returns the squares occupied by black pieces.
+    pub fn black_pieces(&self) -> u64 {
+        self.black
+    }
+
+    /// Returns the squares occupied by any piece.
+    pub fn occupied(&self) -> u64 {
+        self.all()
+    }
+
+    /// Returns the squares occupied by the piece type of either color, or 0 for
+    /// `Piece::UNKNOWN`.
     /// # Examples
     /// ```
-    /// use w_chess::Chessboard;
+    /// use w_chess::{Chessboard, Piece};
     /// let board = Chessboard::new();
-    /// assert_eq!(board.is_stalemate(), false);
+    /// assert_eq!(board.piece_bitboard(Piece::KING) & board.white_pieces(), 1 << 4);
     /// ```
-    pub fn is_stalemate(&self) -> bool {
-        !self.is_checked() && !self.has_moves()
+    pub fn piece_bitboard(&self, piece: Piece) -> u64 {
+        match piece {
+            Piece::UNKNOWN => 0,
+            _ => self.pieces[piece as usize],
+        }
     }
 
-    /// Returns if the current position is a fifty moves rule.
+    /// Returns the side to move.
+    pub fn turn(&self) -> Color {
+        self.turn.into()
+    }
+
+    /// Returns `true` when white is to move, like the boolean colors of `piece_at`.
     /// # Examples
     /// ```
     /// use w_chess::Chessboard;
-    /// let board = Chessboard::new();
-    /// assert_eq!(board.is_fifty_moves(), false);
+    /// let mut board = Chessboard::new();
+    /// assert!(board.side_to_move());
+    /// board.move_to("e4").unwrap();
+    /// assert!(!board.side_to_move());
     /// ```
-    pub fn is_fifty_moves(&self) -> bool {
-        self.half_move >= 100
+    pub fn side_to_move(&self) -> bool {
+        self.turn
     }
 
-    /// Returns if the current position is a threefold repetition.
+    /// Returns the castling rights in `KQkq` order.
+    pub fn castling_rights(&self) -> [bool; 4] {
+        self.castle_rights
+    }
+
+    /// Returns the rook's origin and destination when the color castles to the side, or `None`
+    /// if the color no longer has that castling right. The castling may still be illegal right
+    /// now, for example because a piece stands in the way.
     /// # Examples
     /// ```
-    /// use w_chess::Chessboard;
+    /// use w_chess::{CastlingType, Chessboard, Color, Square};
     /// let board = Chessboard::new();
-    /// assert_eq!(board.is_threefold_repetition(), false);
+    /// assert_eq!(board.castling_rook_move(CastlingType::KingSide, Color::White), Some((Square::H1, Square::F1)));
     /// ```
-    pub fn is_threefold_repetition(&self) -> bool {
-        let mut max = 0;
+    pub fn castling_rook_move(&self, side: CastlingType, color: Color) -> Option<(Square, Square)> {
+        let index = match (color, side) {
+            (Color::White, CastlingType::KingSide) => 0,
+            (Color::White, CastlingType::QueenSide) => 1,
+            (Color::Black, CastlingType::KingSide) => 2,
+            (Color::Black, CastlingType::QueenSide) => 3,
+        };
 
-        for (_, &count) in self.board_repetitions.iter() {
-            if count > max {
-                max = count;
-            }
+        match self.castle_rights[index] {
+            true => Some(Self::castling_squares(side, color.into()).1),
+            false => None,
         }
-
-        max >= 3
     }
 
-    fn has_moves(&self) -> bool {
-        for &legal_moves in self.legal_moves.values() {
-            if legal_moves != 0 {
-                return true;
+    /// Returns the king's and the rook's origin and destination for castling to the side.
+    fn castling_squares(side: CastlingType, color: bool) -> ((Square, Square), (Square, Square)) {
+        match (color, side) {
+            (true, CastlingType::KingSide) => ((Square::E1, Square::G1), (Square::H1, Square::F1)),
+            (true, CastlingType::QueenSide) => ((Square::E1, Square::C1), (Square::A1, Square::D1)),
+            (false, CastlingType::KingSide) => ((Square::E8, Square::G8), (Square::H8, Square::F8)),
+            (false, CastlingType::QueenSide) => {
+                ((Square::E8, Square::C8), (Square::A8, Square::D8))
             }
         }
-        false
     }
 
-    fn get_attack_mask(&self, color: bool, board: u64) -> u64 {
-        let mut enemy_attack_mask = match color {
-            true => self.static_black_attack_mask,
-            false => self.static_white_attack_mask,
-        };
-
-        for &dynamic_piece in self.dynamic_piece_squares.iter() {
-            let dynamic_piece_square = 1 << dynamic_piece;
-            let piece = self.get_piece(dynamic_piece_square);
-            let dynamic_piece_color = self.white & dynamic_piece_square != 0;
-
-            if dynamic_piece_color == color {
-                continue;
-            }
+    /// Returns the en passant target square, if any. It is only set after a double push that an
+    /// enemy pawn could capture, matching the FEN the board produces.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let mut board = Chessboard::from_fen("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1");
+    /// board.move_to("e4").unwrap();
+    /// assert_eq!(board.en_passant(), Some(Square::E3));
+    /// ```
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant_square.map(Square::from)
+    }
 
-            match piece {
-                Piece::BISHOP => {
-                    enemy_attack_mask |= self.generate_bishop_moves(dynamic_piece_square, board);
-                }
-                Piece::ROOK => {
-                    enemy_attack_mask |= self.generate_rook_moves(dynamic_piece_square, board);
-                }
-                Piece::QUEEN => {
-                    enemy_attack_mask |= self.generate_queen_moves(dynamic_piece_square, board);
-                }
-                _ => {}
-            }
-        }
+    /// Returns the number of half-moves since the last capture or pawn move.
+    pub fn halfmove_clock(&self) -> u32 {
+        self.half_move
+    }
 
-        enemy_attack_mask
+    /// Returns the full-move number, starting at 1 and incremented after Black's move.
+    pub fn fullmove_number(&self) -> u32 {
+        self.full_move
     }
 
-    /// Moves a piece to the given square in SAN format.
+    /// Returns the Zobrist hash of the position, covering the pieces, the side to move, the
+    /// castling rights and the en passant file when the capture is legal. The move counters
+    /// aren't included. The keys come from a fixed table, so hashes are stable between runs, and
+    /// the hash is kept up to date as moves are played rather than recomputed.
     /// # Examples
     /// ```
     /// use w_chess::Chessboard;
     /// let mut board = Chessboard::new();
-    /// board.move_to("e4");
+    /// let start = board.zobrist_hash();
+    /// for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+    ///     board.move_to(san).unwrap();
+    /// }
+    /// assert_eq!(board.zobrist_hash(), start);
     /// ```
-    pub fn move_to(&mut self, san: &str) {
-        let mut san = SanMove::parse(san);
-
-        let mut has_moved = false;
-        if let Ok(ref mut valid_san) = san {
-            let to_square = valid_san.to;
-            if valid_san.piece == Piece::KING
-                && ((self.turn
-                    && self.castle_rights[0]
-                    && to_square & WHITE_KING_SIDE_CASTLE_SQUARE != 0)
-                    || (!self.turn
-                        && self.castle_rights[2]
-                        && to_square & BLACK_KING_SIDE_CASTLE_SQUARE != 0))
-                || valid_san.castling == Some(CastlingType::KingSide)
-            {
-                match self.turn {
-                    true => {
-                        if let Some(legal_moves) = self.legal_moves.get(&Square::E1.into()) {
-                            let g1: u64 = Square::G1.into();
-
-                            if legal_moves & g1 != 0 {
-                                self.half_move += 1;
-                                let e1: u64 = Square::E1.into();
-
-                                self.pieces[Piece::KING as usize] ^= e1;
-                                self.pieces[Piece::KING as usize] |= g1;
-                                self.white ^= e1;
-                                self.white |= g1;
+    pub fn zobrist_hash(&self) -> u64 {
+        self.zobrist
+    }
 
-                                let f1: u64 = Square::F1.into();
-                                let h1: u64 = Square::H1.into();
+    fn zobrist_key(&self) -> u64 {
+        let mut key = 0;
 
-                                self.pieces[Piece::ROOK as usize] ^= h1;
-                                self.pieces[Piece::ROOK as usize] |= f1;
-                                self.white ^= h1;
-                                self.white |= f1;
+        for square in Self::get_squares(self.all()) {
+            let index = square.trailing_zeros() as usize;
+            let color = self.white & square != 0;
+            key ^= zobrist::piece_key(self.get_piece(square) as usize, color, index);
+        }
 
-                                self.turn = !self.turn;
-                                self.castle_rights[0] = false;
-                                self.castle_rights[1] = false;
-                                has_moved = true;
-                            }
-                        }
-                    }
-                    false => {
-                        if let Some(legal_moves) = self.legal_moves.get(&Square::E8.into()) {
-                            let g8: u64 = Square::G8.into();
+        if !self.turn {
+            key ^= zobrist::turn_key();
+        }
 
-                            if legal_moves & g8 != 0 {
-                                self.half_move += 1;
-                                let e8: u64 = Square::E8.into();
+        for (index, &castle_right) in self.castle_rights.iter().enumerate() {
+            if castle_right {
+                key ^= zobrist::castling_key(index);
+            }
+        }
 
-                                self.pieces[Piece::KING as usize] ^= e8;
-                                self.pieces[Piece::KING as usize] |= g8;
-                                self.black ^= e8;
-                                self.black |= g8;
+        key ^ self.en_passant_zobrist_key()
+    }
 
-                                let f8: u64 = Square::F8.into();
-                                let h8: u64 = Square::H8.into();
+    fn en_passant_zobrist_key(&self) -> u64 {
+        // The en passant square only distinguishes positions when the capture is legal.
+        match self.en_passant_square {
+            Some(en_passant_square) if self.can_capture_en_passant() => {
+                zobrist::en_passant_key(en_passant_square.trailing_zeros() as usize % 8)
+            }
+            _ => 0,
+        }
+    }
 
-                                self.pieces[Piece::ROOK as usize] ^= h8;
-                                self.pieces[Piece::ROOK as usize] |= f8;
-                                self.black ^= h8;
-                                self.black |= f8;
+    fn toggle_zobrist_piece(&mut self, piece: Piece, color: bool, square: u64) {
+        self.zobrist ^= zobrist::piece_key(piece as usize, color, square.trailing_zeros() as usize);
+    }
 
-                                self.turn = !self.turn;
-                                self.full_move += 1;
-                                self.castle_rights[2] = false;
-                                self.castle_rights[3] = false;
-                                has_moved = true;
-                            }
+    /// Returns the FEN string of the current position.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.get_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    /// ```
+    pub fn get_fen(&self) -> String {
+        let mut fen = String::new();
+        for rank in 0..8 {
+            let mut empty = 0;
+            for file in 0..8 {
+                let square = 1 << (56 - rank as u64 * 8 + file);
+                let color = self.white & square != 0;
+                match self.get_piece(square) {
+                    Piece::PAWN => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        match color {
+                            true => fen.push('P'),
+                            false => fen.push('p'),
                         }
                     }
-                }
-            } else if valid_san.piece == Piece::KING
-                && ((self.turn
-                    && self.castle_rights[1]
-                    && to_square & WHITE_QUEEN_SIDE_CASTLE_SQUARE != 0)
-                    || (!self.turn
-                        && self.castle_rights[3]
-                        && to_square & BLACK_QUEEN_SIDE_CASTLE_SQUARE != 0))
-                || valid_san.castling == Some(CastlingType::QueenSide)
-            {
-                match self.turn {
-                    true => {
-                        if let Some(legal_moves) = self.legal_moves.get(&Square::E1.into()) {
-                            let c1: u64 = Square::C1.into();
-
-                            if legal_moves & c1 != 0 {
-                                self.half_move += 1;
-                                let e1: u64 = Square::E1.into();
-
-                                self.pieces[Piece::KING as usize] ^= e1;
-                                self.pieces[Piece::KING as usize] |= c1;
-                                self.white ^= e1;
-                                self.white |= c1;
-
-                                let a1: u64 = Square::A1.into();
-                                let d1: u64 = Square::D1.into();
-
-                                self.pieces[Piece::ROOK as usize] ^= a1;
-                                self.pieces[Piece::ROOK as usize] |= d1;
-                                self.white ^= a1;
-                                self.white |= d1;
-
-                                self.turn = !self.turn;
-                                self.castle_rights[0] = false;
-                                self.castle_rights[1] = false;
-                                has_moved = true;
-                            }
+                    Piece::KNIGHT => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        match color {
+                            true => fen.push('N'),
+                            false => fen.push('n'),
                         }
                     }
-                    false => {
-                        if let Some(legal_moves) = self.legal_moves.get(&Square::E8.into()) {
-                            let c8: u64 = Square::C8.into();
+                    Piece::BISHOP => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        match color {
+                            true => fen.push('B'),
+                            false => fen.push('b'),
+                        }
+                    }
+                    Piece::ROOK => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        match color {
+                            true => fen.push('R'),
+                            false => fen.push('r'),
+                        }
+                    }
+                    Piece::QUEEN => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        match color {
+                            true => fen.push('Q'),
+                            false => fen.push('q'),
+                        }
+                    }
+                    Piece::KING => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        match color {
+                            true => fen.push('K'),
+                            false => fen.push('k'),
+                        }
+                    }
+                    _ => {
+                        empty += 1;
+                    }
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank < 7 {
+                fen.push('/');
+            }
+        }
 
-                            if legal_moves & c8 != 0 {
-                                self.half_move += 1;
-                                let e8: u64 = Square::E8.into();
+        let turn = if self.turn { "w" } else { "b" };
 
-                                self.pieces[Piece::KING as usize] ^= e8;
-                                self.pieces[Piece::KING as usize] |= c8;
-                                self.black ^= e8;
-                                self.black |= c8;
+        let castle_rights = self.castling_display();
 
-                                let d8: u64 = Square::D8.into();
-                                let a8: u64 = Square::A8.into();
+        let en_passant_square = match self.en_passant_square {
+            Some(square) => Square::from(square).to_string(),
+            None => "-".to_string(),
+        };
 
-                                self.pieces[Piece::ROOK as usize] ^= a8;
-                                self.pieces[Piece::ROOK as usize] |= d8;
-                                self.black ^= a8;
-                                self.black |= d8;
+        format!(
+            "{} {} {} {} {} {}",
+            fen, turn, castle_rights, en_passant_square, self.half_move, self.full_move
+        )
+    }
 
-                                self.turn = !self.turn;
-                                self.full_move += 1;
-                                self.castle_rights[2] = false;
-                                self.castle_rights[3] = false;
-                                has_moved = true;
-                            }
-                        }
-                    }
-                }
-            } else if let Some(promotion_piece) = valid_san.promotion {
-                'search: for (&from_square, &legal_moves) in self.legal_moves.iter() {
-                    let piece = self.get_piece(from_square);
-                    let color = self.white & from_square != 0;
-
-                    if self.turn != color
-                        || (piece != valid_san.piece)
-                        || (valid_san.from > 0 && from_square & valid_san.from == 0)
-                    {
-                        continue;
-                    }
+    /// Returns the FEN with white to move: when black is to move, the position is mirrored by
+    /// flipping the ranks and swapping the colors, so symmetric positions share one FEN.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("4k3/4p3/8/8/8/8/8/4K3 b - - 0 1");
+    /// assert_eq!(board.canonical_fen(), "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+    /// ```
+    pub fn canonical_fen(&self) -> String {
+        let fen = self.get_fen();
 
-                    match piece {
-                        Piece::PAWN => {
-                            for valid_square in Chessboard::get_squares(legal_moves) {
-                                if valid_square & to_square != 0 {
-                                    self.half_move = 0;
-
-                                    let before = self.get_fen();
-                                    let mut captured = None;
-
-                                    self.pieces[piece as usize] ^= from_square;
-
-                                    match color {
-                                        true => {
-                                            self.white ^= from_square;
-                                            self.white |= valid_square;
-
-                                            if self.black & valid_square != 0 {
-                                                let captured_piece = self.get_piece(valid_square);
-                                                self.pieces[captured_piece as usize] ^=
-                                                    valid_square;
-                                                self.black ^= valid_square;
-                                                captured = Some(captured_piece);
-                                            }
-                                        }
-                                        false => {
-                                            self.black ^= from_square;
-                                            self.black |= valid_square;
-
-                                            if self.white & valid_square != 0 {
-                                                let captured_piece = self.get_piece(valid_square);
-                                                self.pieces[captured_piece as usize] ^=
-                                                    valid_square;
-                                                self.white ^= valid_square;
-                                                captured = Some(captured_piece);
-                                            }
-                                        }
-                                    }
+        if self.turn {
+            return fen;
+        }
 
-                                    self.pieces[promotion_piece as usize] |= valid_square;
+        let parts: Vec<&str> = fen.split(' ').collect();
+        let swap_case = |c: char| match c.is_ascii_uppercase() {
+            true => c.to_ascii_lowercase(),
+            false => c.to_ascii_uppercase(),
+        };
 
-                                    let after = self.get_fen();
+        let placement: Vec<String> = parts[0]
+            .split('/')
+            .rev()
+            .map(|rank| rank.chars().map(swap_case).collect())
+            .collect();
+
+        let mut castling: Vec<char> = parts[2].chars().map(swap_case).collect();
+        castling.sort_by_key(|&c| "KQkq-".find(c));
+
+        let en_passant = match parts[3] {
+            "-" => "-".to_string(),
+            square => square
+                .chars()
+                .map(|c| match c {
+                    '3' => '6',
+                    '6' => '3',
+                    _ => c,
+                })
+                .collect(),
+        };
 
-                                    self.board_repetitions
-                                        .entry(after.clone())
-                                        .and_modify(|count| *count += 1)
-                                        .or_insert(1);
+        format!(
+            "{} w {} {} {} {}",
+            placement.join("/"),
+            castling.into_iter().collect::<String>(),
+            en_passant,
+            parts[4],
+            parts[5]
+        )
+    }
 
-                                    self.history.push(ChessMove::new(
-                                        valid_san,
-                                        self.turn,
-                                        before,
-                                        after,
-                                        from_square,
-                                        captured,
-                                    ));
+    /// Returns the castling availability as it appears in the FEN, such as `KQkq` or `-`.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1");
+    /// assert_eq!(board.castling_display(), "Kq");
+    /// ```
+    pub fn castling_display(&self) -> String {
+        let mut castle_rights = String::new();
 
-                                    self.turn = !self.turn;
-                                    has_moved = true;
-                                    break 'search;
-                                }
-                            }
-                        }
-                        _ => {
-                            continue;
-                        }
-                    }
-                }
-            } else {
-                'search: for (&from_square, &legal_moves) in self.legal_moves.iter() {
-                    let piece = self.get_piece(from_square);
-                    let color = self.white & from_square != 0;
-
-                    if self.turn != color
-                        || (piece != valid_san.piece)
-                        || (valid_san.from > 0 && from_square & valid_san.from == 0)
-                    {
-                        continue;
-                    }
+        if self.castle_rights[0] {
+            castle_rights.push('K');
+        }
+        if self.castle_rights[1] {
+            castle_rights.push('Q');
+        }
+        if self.castle_rights[2] {
+            castle_rights.push('k');
+        }
+        if self.castle_rights[3] {
+            castle_rights.push('q');
+        }
 
-                    for valid_square in Chessboard::get_squares(legal_moves) {
-                        if valid_square & to_square != 0 {
-                            let before = self.get_fen();
-                            let mut captured = None;
-
-                            if let Some(en_passant_square) = self.en_passant_square.take() {
-                                if valid_square == en_passant_square && piece == Piece::PAWN {
-                                    self.pieces[Piece::PAWN as usize] ^= en_passant_square;
-                                    match self.turn {
-                                        true => {
-                                            self.black ^= en_passant_square >> 8;
-                                            self.pieces[Piece::PAWN as usize] ^=
-                                                en_passant_square >> 8;
-                                        }
-                                        false => {
-                                            self.white ^= en_passant_square << 8;
-                                            self.pieces[Piece::PAWN as usize] ^=
-                                                en_passant_square << 8;
-                                        }
-                                    }
-                                }
-                            }
+        if castle_rights.is_empty() {
+            castle_rights = "-".to_string();
+        }
 
-                            match piece {
-                                Piece::KING => match self.turn {
-                                    true => {
-                                        self.castle_rights[0] = false;
-                                        self.castle_rights[1] = false;
-                                    }
-                                    false => {
-                                        self.castle_rights[2] = false;
-                                        self.castle_rights[3] = false;
-                                    }
-                                },
-                                Piece::ROOK => match self.turn {
-                                    true => {
-                                        let h1: u64 = Square::H1.into();
-                                        let a1: u64 = Square::A1.into();
-                                        if from_square & h1 != 0 {
-                                            self.castle_rights[0] = false;
-                                        } else if from_square & a1 != 0 {
-                                            self.castle_rights[1] = false;
-                                        }
-                                    }
-                                    false => {
-                                        let h8: u64 = Square::H8.into();
-                                        let a8: u64 = Square::A8.into();
-                                        if from_square & h8 != 0 {
-                                            self.castle_rights[2] = false;
-                                        } else if from_square & a8 != 0 {
-                                            self.castle_rights[3] = false;
-                                        }
-                                    }
-                                },
-                                Piece::PAWN => match self.turn {
-                                    true => {
-                                        if from_square & RANK_2 != 0 {
-                                            if valid_square & RANK_4 != 0 {
-                                                let black_pawns =
-                                                    self.pieces[Piece::PAWN as usize] & self.black;
-                                                if valid_square & FILE_A == 0
-                                                    && black_pawns & (valid_square >> 1) != 0
-                                                {
-                                                    self.en_passant_square =
-                                                        Some(valid_square >> 8);
-                                                }
-
-                                                if valid_square & FILE_H == 0
-                                                    && black_pawns & (valid_square << 1) != 0
-                                                {
-                                                    self.en_passant_square =
-                                                        Some(valid_square >> 8);
-                                                }
-                                            }
-                                        }
-                                    }
-                                    false => {
-                                        if from_square & RANK_7 != 0 {
-                                            if valid_square & RANK_5 != 0 {
-                                                let white_pawns =
-                                                    self.pieces[Piece::PAWN as usize] & self.white;
-                                                if valid_square & FILE_A == 0
-                                                    && white_pawns & valid_square >> 1 != 0
-                                                {
-                                                    self.en_passant_square =
-                                                        Some(valid_square << 8);
-                                                }
-
-                                                if valid_square & FILE_H == 0
-                                                    && white_pawns & valid_square << 1 != 0
-                                                {
-                                                    self.en_passant_square =
-                                                        Some(valid_square << 8);
-                                                }
-                                            }
-                                        }
-                                    }
-                                },
-                                _ => {}
-                            }
+        castle_rights
+    }
 
-                            self.pieces[piece as usize] ^= from_square;
+    /// Returns if the current position is checked.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.is_checked(), false);
+    /// ```
+    pub fn is_checked(&self) -> bool {
+        let king = self.pieces[Piece::KING as usize] & self.get_color(self.turn);
+        let enemy_attack_mask = self.get_attack_mask(self.turn, self.all());
 
-                            match self.turn {
-                                true => {
-                                    self.white ^= from_square;
-                                    self.white |= valid_square;
-
-                                    if self.black & valid_square != 0 {
-                                        let captured_piece = self.get_piece(valid_square);
-                                        self.pieces[captured_piece as usize] ^= valid_square;
-                                        self.black ^= valid_square;
-                                        self.half_move = 0;
-                                        captured = Some(captured_piece);
-                                    } else {
-                                        match piece {
-                                            Piece::PAWN => {
-                                                self.half_move = 0;
-                                            }
-                                            _ => {
-                                                self.half_move += 1;
-                                            }
-                                        }
-                                    }
-                                }
-                                false => {
-                                    self.black ^= from_square;
-                                    self.black |= valid_square;
-
-                                    if self.white & valid_square != 0 {
-                                        let captured_piece = self.get_piece(valid_square);
-                                        self.pieces[captured_piece as usize] ^= valid_square;
-                                        self.white ^= valid_square;
-                                        self.half_move = 0;
-                                        captured = Some(captured_piece);
-                                    } else {
-                                        match piece {
-                                            Piece::PAWN => {
-                                                self.half_move = 0;
-                                            }
-                                            _ => {
-                                                self.half_move += 1;
-                                            }
-                                        }
-                                    }
+        enemy_attack_mask & king != 0
+    }
 
-                                    self.full_move += 1;
-                                }
-                            }
+    /// Returns the square of the color's king, or `Square::UNKNOWN` if it has none.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Color, Square};
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.king_square(Color::White), Square::E1);
+    /// ```
+    pub fn king_square(&self, color: Color) -> Square {
+        Square::from(self.pieces[Piece::KING as usize] & self.get_color(color.into()))
+    }
 
-                            self.pieces[piece as usize] |= valid_square;
-                            let after = self.get_fen();
-
-                            self.board_repetitions
-                                .entry(after.split_whitespace().next().unwrap().to_string())
-                                .and_modify(|count| *count += 1)
-                                .or_insert(1);
-
-                            self.history.push(ChessMove::new(
-                                valid_san,
-                                self.turn,
-                                before,
-                                after,
-                                from_square,
-                                captured,
-                            ));
-
-                            self.turn = !self.turn;
-                            has_moved = true;
-                            break 'search;
-                        }
-                    }
-                }
-            }
-            if has_moved {
-                self.generate_legal_moves();
-            } else {
-                println!(
-                    "Invalid move {} to {}",
-                    valid_san.piece,
-                    Square::from(valid_san.to)
-                );
-            }
+    /// Returns if only kings and pawns are left on the board.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("4k3/4p3/8/8/8/8/4P3/4K3 w - - 0 1");
+    /// assert!(board.is_pawn_endgame());
+    /// ```
+    pub fn is_pawn_endgame(&self) -> bool {
+        (self.pieces[Piece::KNIGHT as usize]
+            | self.pieces[Piece::BISHOP as usize]
+            | self.pieces[Piece::ROOK as usize]
+            | self.pieces[Piece::QUEEN as usize])
+            == 0
+    }
+
+    /// Returns the theoretical result of a king and pawn against king position with best play,
+    /// or `None` for any other material. The result comes from a table of every such position,
+    /// built the first time this is called.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, GameResult};
+    /// let board = Chessboard::from_fen("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1");
+    /// assert_eq!(board.kpk_result(), Some(GameResult::WhiteWins));
+    /// ```
+    pub fn kpk_result(&self) -> Option<GameResult> {
+        let pawns = self.pieces[Piece::PAWN as usize];
+
+        if self.all().count_ones() != 3 || pawns.count_ones() != 1 {
+            return None;
+        }
+
+        let strong = self.white & pawns != 0;
+        let strong_king = self.pieces[Piece::KING as usize] & self.get_color(strong);
+        let weak_king = self.pieces[Piece::KING as usize] & self.get_color(!strong);
+
+        if strong_king == 0 || weak_king == 0 || pawns & (RANK_1 | RANK_8) != 0 {
+            return None;
+        }
+
+        // Look the position up from the side of the pawn, as a white pawn on files a to d.
+        let mut squares = [strong_king, weak_king, pawns].map(|square| square.trailing_zeros());
+        if !strong {
+            squares = squares.map(|square| square ^ 56);
+        }
+        if squares[2] % 8 > 3 {
+            squares = squares.map(|square| square ^ 7);
+        }
+
+        let [strong_king, weak_king, pawn] = squares.map(|square| square as usize);
+        let wins = kpk::probe(self.turn == strong, strong_king, weak_king, pawn)?;
+
+        match (wins, strong) {
+            (true, true) => Some(GameResult::WhiteWins),
+            (true, false) => Some(GameResult::BlackWins),
+            (false, _) => Some(GameResult::Draw),
         }
     }
 
-    fn get_squares(bitboard: u64) -> Vec<u64> {
-        let mut squares = Vec::new();
-        for i in 0..64 {
-            let square = 1 << i;
-            if bitboard & square != 0 {
-                squares.push(square);
-            }
+    /// Returns if the position could occur in a game: each side has exactly one king, no pawn
+    /// stands on the first or last rank, and the side that just moved isn't in check.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// assert!(Chessboard::new().is_position_legal());
+    /// assert!(!Chessboard::from_fen("4k3/8/8/8/8/8/8/4K2r b - - 0 1").is_position_legal());
+    /// ```
+    pub fn is_position_legal(&self) -> bool {
+        let kings = self.pieces[Piece::KING as usize];
+        let waiting_king = kings & self.get_color(!self.turn);
+
+        (kings & self.white).count_ones() == 1
+            && (kings & self.black).count_ones() == 1
+            && self.pieces[Piece::PAWN as usize] & (RANK_1 | RANK_8) == 0
+            && self.attackers_to(waiting_king, self.turn) == 0
+    }
+
+    /// Returns the squares the king of the color can move to without being attacked, including
+    /// captures of undefended pieces. Castling isn't included. Returns 0 if there is no such
+    /// king.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Color, Square};
+    /// let board = Chessboard::from_fen("8/8/8/8/8/8/r7/4K2k w - - 0 1");
+    /// assert_eq!(board.king_safe_squares(Color::White), u64::from(Square::D1) | u64::from(Square::F1));
+    /// ```
+    pub fn king_safe_squares(&self, color: Color) -> u64 {
+        let color: bool = color.into();
+        let king = self.pieces[Piece::KING as usize] & self.get_color(color);
+
+        if king == 0 {
+            return 0;
         }
-        squares
+
+        let enemy_king = self.pieces[Piece::KING as usize] & self.get_color(!color);
+        let candidates = self.generate_king_moves(king)
+            & !self.get_color(color)
+            & !self.generate_king_moves(enemy_king);
+        let board_without_king = self.all() & !king;
+
+        Self::get_squares(candidates)
+            .into_iter()
+            .filter(|&square| {
+                let captured = square & self.get_color(!color);
+                self.get_attack_mask_without(color, board_without_king | square, captured) & square
+                    == 0
+            })
+            .fold(0, |mask, square| mask | square)
     }
 
-    /// Returns the ASCII representation of the current position.
+    /// Returns if neither side has enough material to checkmate: king against king, king and a
+    /// single minor piece against king, or kings and bishops that all stand on the same color.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/4KB2 w - - 0 1");
+    /// assert!(board.is_insufficient_material());
+    /// ```
+    pub fn is_insufficient_material(&self) -> bool {
+        let major_pieces = self.pieces[Piece::PAWN as usize]
+            | self.pieces[Piece::ROOK as usize]
+            | self.pieces[Piece::QUEEN as usize];
+
+        if major_pieces != 0 {
+            return false;
+        }
+
+        let knights = self.pieces[Piece::KNIGHT as usize];
+        let bishops = self.pieces[Piece::BISHOP as usize];
+
+        if (knights | bishops).count_ones() <= 1 {
+            return true;
+        }
+
+        knights == 0 && (bishops & DARK_SQUARES == 0 || bishops & !DARK_SQUARES == 0)
+    }
+
+    /// Returns if the current position is a checkmate.
     /// # Examples
     /// ```
     /// use w_chess::Chessboard;
     /// let board = Chessboard::new();
-    /// println!("{}", board.ascii());
+    /// assert_eq!(board.is_mate(), false);
     /// ```
-    pub fn ascii(&self) -> String {
-        let mut board = String::new();
-        for rank in 0_u64..8 {
-            for file in 0_u64..8 {
-                let square = 1 << 56 - rank * 8 + file;
-                let piece = self.get_piece(square);
+    pub fn is_mate(&self) -> bool {
+        self.is_checked() && !self.has_moves()
+    }
 
-                if square & self.all() != 0 {
-                    let color = self.white & square != 0;
-                    match piece {
-                        Piece::PAWN => match color {
-                            true => {
-                                board.push('\u{2659}');
-                            }
-                            false => {
-                                board.push('\u{265F}');
-                            }
-                        },
-                        Piece::KNIGHT => match color {
-                            true => {
-                                board.push('\u{2658}');
-                            }
-                            false => {
-                                board.push('\u{265E}');
-                            }
-                        },
-                        Piece::BISHOP => match color {
-                            true => {
-                                board.push('\u{2657}');
-                            }
-                            false => {
-                                board.push('\u{265D}');
-                            }
-                        },
-                        Piece::ROOK => match color {
-                            true => {
-                                board.push('\u{2656}');
-                            }
-                            false => {
-                                board.push('\u{265C}');
-                            }
-                        },
-                        Piece::QUEEN => match color {
-                            true => {
-                                board.push('\u{2655}');
-                            }
-                            false => {
-                                board.push('\u{265B}');
-                            }
-                        },
-                        Piece::KING => match color {
-                            true => {
-                                board.push('\u{2654}');
-                            }
-                            false => {
-                                board.push('\u{265A}');
-                            }
-                        },
-                        _ => {}
-                    }
-                } else {
-                    board.push('\u{25A1}');
-                }
-                board.push(' ');
+    /// Returns if the current position is a stalemate.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.is_stalemate(), false);
+    /// ```
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_checked() && !self.has_moves()
+    }
+
+    /// Returns if the current position is a fifty moves rule.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.is_fifty_moves(), false);
+    /// ```
+    pub fn is_fifty_moves(&self) -> bool {
+        self.half_move >= 100
+    }
+
+    /// Returns if the current position is a threefold repetition. Only the positions since the
+    /// last capture or pawn move are searched, as earlier ones can't repeat.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.is_threefold_repetition(), false);
+    /// ```
+    pub fn is_threefold_repetition(&self) -> bool {
+        let reversible = self.position_keys.len().min(self.half_move as usize + 1);
+
+        match self.position_keys.last() {
+            Some(current_key) => {
+                self.position_keys
+                    .iter()
+                    .rev()
+                    .take(reversible)
+                    .filter(|&key| key == current_key)
+                    .count()
+                    >= 3
             }
+            None => false,
+        }
+    }
+
+    /// Returns if every pawn is blocked by an enemy pawn directly ahead and no pawn can capture.
+    /// This is a heuristic for adjudicating locked positions and doesn't mean the game is drawn:
+    /// the pieces may still be able to break through.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("4k3/8/8/3p4/3P4/8/8/4K3 w - - 0 1");
+    /// assert!(board.is_blocked_position());
+    /// ```
+    pub fn is_blocked_position(&self) -> bool {
+        let pawns = self.pieces[Piece::PAWN as usize];
+        let white_pawns = pawns & self.white;
+        let black_pawns = pawns & self.black;
+
+        if pawns == 0
+            || (white_pawns << 8) & !black_pawns != 0
+            || (black_pawns >> 8) & !white_pawns != 0
+        {
+            return false;
+        }
+
+        Self::get_squares(pawns).into_iter().all(|square| {
+            let color = self.white & square != 0;
+            self.generate_pawn_attacks(square, color) & self.get_color(!color) == 0
+        })
+    }
+
+    /// Returns why the game is over, if it is. Checkmate takes priority over every draw, so a
+    /// mate on the move that reaches the fifty move rule or a threefold repetition still wins.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Termination};
+    /// let board = Chessboard::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    /// assert_eq!(board.termination(), Some(Termination::Stalemate));
+    /// ```
+    pub fn termination(&self) -> Option<Termination> {
+        if self.is_mate() {
+            Some(Termination::Checkmate)
+        } else if self.is_stalemate() {
+            Some(Termination::Stalemate)
+        } else if self.is_insufficient_material() {
+            Some(Termination::InsufficientMaterial)
+        } else if self.is_fifty_moves() {
+            Some(Termination::FiftyMoves)
+        } else if self.is_threefold_repetition() {
+            Some(Termination::ThreefoldRepetition)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the result of the game if it is over: a win for the side that delivered
+    /// checkmate, or a draw by stalemate, insufficient material, the fifty move rule or
+    /// threefold repetition.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, GameResult};
+    /// let board = Chessboard::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+    /// assert_eq!(board.result(), Some(GameResult::BlackWins));
+    /// assert_eq!(Chessboard::new().result(), None);
+    /// ```
+    pub fn result(&self) -> Option<GameResult> {
+        match self.termination()? {
+            Termination::Checkmate => match self.turn {
+                true => Some(GameResult::BlackWins),
+                false => Some(GameResult::WhiteWins),
+            },
+            _ => Some(GameResult::Draw),
+        }
+    }
+
+    /// Returns the state of the game, with checkmate taking priority over every draw like
+    /// `termination`.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, GameStatus};
+    /// let board = Chessboard::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+    /// assert_eq!(board.status(), GameStatus::Checkmate { winner: false });
+    /// assert_eq!(Chessboard::new().status(), GameStatus::Ongoing);
+    /// ```
+    pub fn status(&self) -> GameStatus {
+        match self.termination() {
+            None => GameStatus::Ongoing,
+            Some(Termination::Checkmate) => GameStatus::Checkmate { winner: !self.turn },
+            Some(Termination::Stalemate) => GameStatus::Stalemate,
+            Some(Termination::InsufficientMaterial) => GameStatus::InsufficientMaterial,
+            Some(Termination::FiftyMoves) => GameStatus::FiftyMove,
+            Some(Termination::ThreefoldRepetition) => GameStatus::ThreefoldRepetition,
+        }
+    }
+
+    /// Returns if the game is drawn by stalemate, insufficient material, the fifty move rule or
+    /// threefold repetition.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// assert!(Chessboard::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").is_draw());
+    /// assert!(!Chessboard::new().is_draw());
+    /// ```
+    pub fn is_draw(&self) -> bool {
+        self.result() == Some(GameResult::Draw)
+    }
+
+    /// Returns if the game is over, by checkmate or a draw.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// assert!(!Chessboard::new().is_game_over());
+    /// ```
+    pub fn is_game_over(&self) -> bool {
+        self.termination().is_some()
+    }
+
+    fn has_moves(&self) -> bool {
+        for &legal_moves in self.legal_moves.values() {
+            if legal_moves != 0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn get_attack_mask(&self, color: bool, board: u64) -> u64 {
+        let static_attack_mask = match color {
+            true => self.static_black_attack_mask,
+            false => self.static_white_attack_mask,
+        };
+
+        static_attack_mask | self.get_dynamic_attack_mask(!color, board)
+    }
+
+    fn get_dynamic_attack_mask(&self, color: bool, board: u64) -> u64 {
+        let mut attack_mask = 0;
+
+        for &dynamic_piece in self.dynamic_piece_squares.iter() {
+            let dynamic_piece_square = 1 << dynamic_piece;
+            let piece = self.get_piece(dynamic_piece_square);
+            let dynamic_piece_color = self.white & dynamic_piece_square != 0;
+
+            if dynamic_piece_color != color {
+                continue;
+            }
+
+            match piece {
+                Piece::BISHOP => {
+                    attack_mask |= self.generate_bishop_moves(dynamic_piece_square, board);
+                }
+                Piece::ROOK => {
+                    attack_mask |= self.generate_rook_moves(dynamic_piece_square, board);
+                }
+                Piece::QUEEN => {
+                    attack_mask |= self.generate_queen_moves(dynamic_piece_square, board);
+                }
+                _ => {}
+            }
+        }
+
+        attack_mask
+    }
+
+    pub(crate) fn can_capture_en_passant(&self) -> bool {
+        let en_passant_square = match self.en_passant_square {
+            Some(en_passant_square) => en_passant_square,
+            None => return false,
+        };
+
+        Self::get_squares(self.get_color(self.turn) & self.pieces[Piece::PAWN as usize])
+            .into_iter()
+            .any(|square| {
+                self.legal_moves
+                    .get(&square)
+                    .is_some_and(|&moves| moves & en_passant_square != 0)
+            })
+    }
+
+    /// Returns the number of pseudo-legal destination squares of the color's pieces, not counting
+    /// pawns or castling.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Color};
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.mobility(Color::White), 4);
+    /// ```
+    pub fn mobility(&self, color: Color) -> u32 {
+        let color: bool = color.into();
+        let mut mobility = 0;
+
+        for square in Self::get_squares(self.get_color(color) & !self.pieces[Piece::PAWN as usize])
+        {
+            let mut moves = self.pseudo_legal_moves.get(&square).copied().unwrap_or(0);
+
+            if self.get_piece(square) == Piece::KING {
+                moves &= self.generate_king_moves(square);
+            }
+
+            mobility += moves.count_ones();
+        }
+
+        mobility
+    }
+
+    /// Returns the pieces checking the side to move and the squares between a single sliding
+    /// checker and the king.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1");
+    /// let check_info = board.check_info();
+    /// assert_eq!(check_info.checkers, Square::A1.into());
+    /// assert_eq!(check_info.block_squares, u64::from(Square::B1) | u64::from(Square::C1) | u64::from(Square::D1));
+    /// ```
+    pub fn check_info(&self) -> CheckInfo {
+        let king = self.pieces[Piece::KING as usize] & self.get_color(self.turn);
+        if king == 0 {
+            return CheckInfo {
+                checkers: 0,
+                block_squares: 0,
+            };
+        }
+
+        let checkers = self.attackers_to(king, !self.turn);
+        let sliders = self.pieces[Piece::BISHOP as usize]
+            | self.pieces[Piece::ROOK as usize]
+            | self.pieces[Piece::QUEEN as usize];
+
+        let block_squares = match checkers.count_ones() == 1 && checkers & sliders != 0 {
+            true => self.squares_between(king, checkers),
+            false => 0,
+        };
+
+        CheckInfo {
+            checkers,
+            block_squares,
+        }
+    }
+
+    /// Returns the squares attacked by the piece on the square, stopping at the first piece in
+    /// each direction. Pawns only attack diagonally. An empty square attacks nothing.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.attacks_from(Square::G1), u64::from(Square::E2) | u64::from(Square::F3) | u64::from(Square::H3));
+    /// ```
+    pub fn attacks_from(&self, square: Square) -> u64 {
+        let square: u64 = square.into();
+        let board = self.all();
+
+        match self.get_piece(square) {
+            Piece::PAWN => self.generate_pawn_attacks(square, self.white & square != 0),
+            Piece::KNIGHT => self.generate_knight_moves(square),
+            Piece::BISHOP => self.generate_bishop_moves(square, board),
+            Piece::ROOK => self.generate_rook_moves(square, board),
+            Piece::QUEEN => self.generate_queen_moves(square, board),
+            Piece::KING => self.generate_king_moves(square),
+            Piece::UNKNOWN => 0,
+        }
+    }
+
+    /// Returns the number of pieces of the given color attacking the square.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Color, Square};
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.attacker_count(Square::F3, Color::White), 3);
+    /// ```
+    pub fn attacker_count(&self, square: Square, by: Color) -> u8 {
+        self.attackers_to(square.into(), by.into()).count_ones() as u8
+    }
+
+    fn attackers_to(&self, square: u64, color: bool) -> u64 {
+        let attackers = self.get_color(color);
+        let board = self.all();
+
+        let bishops = self.pieces[Piece::BISHOP as usize] | self.pieces[Piece::QUEEN as usize];
+        let rooks = self.pieces[Piece::ROOK as usize] | self.pieces[Piece::QUEEN as usize];
+
+        attackers
+            & ((self.generate_pawn_attacks(square, !color) & self.pieces[Piece::PAWN as usize])
+                | (self.generate_knight_moves(square) & self.pieces[Piece::KNIGHT as usize])
+                | (self.generate_bishop_moves(square, board) & bishops)
+                | (self.generate_rook_moves(square, board) & rooks)
+                | (self.generate_king_moves(square) & self.pieces[Piece::KING as usize]))
+    }
+
+    fn squares_between(&self, a: u64, b: u64) -> u64 {
+        let (a_index, b_index) = (a.trailing_zeros(), b.trailing_zeros());
+        let board = self.all();
+
+        if a_index % 8 == b_index % 8 || a_index / 8 == b_index / 8 {
+            self.generate_rook_moves(a, board) & self.generate_rook_moves(b, board)
+        } else {
+            self.generate_bishop_moves(a, board) & self.generate_bishop_moves(b, board)
+        }
+    }
+
+    /// Returns whether the piece on the square is defended by a piece of its own color.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::new();
+    /// assert!(board.is_defended(Square::E2));
+    /// assert!(!board.is_defended(Square::E4));
+    /// ```
+    pub fn is_defended(&self, square: Square) -> bool {
+        let square: u64 = square.into();
+
+        if self.all() & square == 0 {
+            return false;
+        }
+
+        let color = self.white & square != 0;
+
+        self.get_attack_mask(!color, self.all()) & square != 0
+    }
+
+    /// Returns the attack masks the move generator uses for the current position.
+    /// # Examples
+    /// ```
+    /// use w_chess::{format_mask, Chessboard};
+    /// let board = Chessboard::new();
+    /// println!("{}", format_mask(board.debug_masks().static_white_attack_mask));
+    /// ```
+    pub fn debug_masks(&self) -> DebugMasks {
+        DebugMasks {
+            static_white_attack_mask: self.static_white_attack_mask,
+            static_black_attack_mask: self.static_black_attack_mask,
+            dynamic_white_attack_mask: self.get_dynamic_attack_mask(true, self.all()),
+            dynamic_black_attack_mask: self.get_dynamic_attack_mask(false, self.all()),
+        }
+    }
+
+    /// Returns the squares attacked by the color's pieces as if the squares in `remove` were
+    /// empty. Removed pieces don't attack and no longer block.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Color, Square};
+    /// let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/R2NK3 w - - 0 1");
+    /// assert_eq!(board.attack_map_without(0, Color::White) & u64::from(Square::E1), 0);
+    /// assert_ne!(board.attack_map_without(Square::D1.into(), Color::White) & u64::from(Square::E1), 0);
+    /// ```
+    pub fn attack_map_without(&self, remove: u64, color: Color) -> u64 {
+        let color: bool = color.into();
+        self.get_attack_mask_without(!color, self.all() & !remove, remove)
+    }
+
+    fn get_attack_mask_without(&self, color: bool, board: u64, removed: u64) -> u64 {
+        let mut enemy_attack_mask = 0;
+
+        for square in Self::get_squares(self.get_color(!color) & !removed) {
+            enemy_attack_mask |= match self.get_piece(square) {
+                Piece::PAWN => self.generate_pawn_attacks(square, !color),
+                Piece::KNIGHT => self.generate_knight_moves(square),
+                Piece::BISHOP => self.generate_bishop_moves(square, board),
+                Piece::ROOK => self.generate_rook_moves(square, board),
+                Piece::QUEEN => self.generate_queen_moves(square, board),
+                Piece::KING => self.generate_king_moves(square),
+                Piece::UNKNOWN => 0,
+            };
+        }
+
+        enemy_attack_mask
+    }
+
+    fn get_captured_square(&self, from: u64, to: u64) -> u64 {
+        let color = self.white & from != 0;
+
+        if self.get_color(!color) & to != 0 {
+            return to;
+        }
+
+        if self.get_piece(from) == Piece::PAWN && self.en_passant_square == Some(to) {
+            return match color {
+                true => to >> 8,
+                false => to << 8,
+            };
+        }
+
+        0
+    }
+
+    /// Returns whether each candidate move, given as origin, destination and promotion piece,
+    /// is legal in the current position.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::new();
+    /// let legal = board.filter_legal(&[(Square::E2, Square::E4, None), (Square::E2, Square::E5, None)]);
+    /// assert_eq!(legal, vec![true, false]);
+    /// ```
+    pub fn filter_legal(&self, candidates: &[(Square, Square, Option<Piece>)]) -> Vec<bool> {
+        candidates
+            .iter()
+            .map(|&(from, to, promotion)| self.is_legal(from.into(), to.into(), promotion))
+            .collect()
+    }
+
+    /// Returns the destinations in `pseudo_targets` that the piece on `from` can legally move
+    /// to, meaning the move doesn't leave its king in check. Pieces of the side not to move
+    /// have no legal destinations.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::new();
+    /// let targets = u64::from(Square::E3) | u64::from(Square::E4) | u64::from(Square::E5);
+    /// assert_eq!(board.legalize(Square::E2, targets), u64::from(Square::E3) | u64::from(Square::E4));
+    /// ```
+    pub fn legalize(&self, from: Square, pseudo_targets: u64) -> u64 {
+        let from: u64 = from.into();
+
+        if from & self.get_color(self.turn) == 0 {
+            return 0;
+        }
+
+        self.legal_moves.get(&from).copied().unwrap_or(0) & pseudo_targets
+    }
+
+    /// Returns the legal destinations of the piece on the square, including castling for the
+    /// king, or no squares if it isn't a piece of the side to move.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.legal_moves_from(Square::E2), vec![Square::E3, Square::E4]);
+    /// assert!(board.legal_moves_from(Square::E7).is_empty());
+    /// ```
+    pub fn legal_moves_from(&self, square: Square) -> Vec<Square> {
+        let from: u64 = square.into();
+
+        if from & self.get_color(self.turn) == 0 {
+            return Vec::new();
+        }
+
+        Self::get_squares(self.legal_moves.get(&from).copied().unwrap_or(0))
+            .into_iter()
+            .map(Square::from)
+            .collect()
+    }
+
+    /// Returns the legal destinations of each piece of the side to move, keyed by origin square.
+    /// Pieces without a legal move aren't included.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::new();
+    /// let moves = board.legal_moves_map();
+    /// assert_eq!(moves[&Square::G1], vec![Square::F3, Square::H3]);
+    /// ```
+    pub fn legal_moves_map(&self) -> HashMap<Square, Vec<Square>> {
+        Self::get_squares(self.get_color(self.turn))
+            .into_iter()
+            .filter_map(|from| match self.legal_moves.get(&from) {
+                Some(&moves) if moves != 0 => Some((
+                    Square::from(from),
+                    Self::get_squares(moves)
+                        .into_iter()
+                        .map(Square::from)
+                        .collect(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn is_legal(&self, from: u64, to: u64, promotion: Option<Piece>) -> bool {
+        if from & self.get_color(self.turn) == 0 {
+            return false;
+        }
+
+        match self.legal_moves.get(&from) {
+            Some(&legal_moves) if legal_moves & to != 0 => {}
+            _ => return false,
+        }
+
+        let promotes = self.get_piece(from) == Piece::PAWN && to & (RANK_1 | RANK_8) != 0;
+
+        match promotion {
+            Some(Piece::KNIGHT | Piece::BISHOP | Piece::ROOK | Piece::QUEEN) => promotes,
+            Some(_) => false,
+            None => !promotes,
+        }
+    }
+
+    /// Moves a piece to the given square in SAN format and returns the move that was recorded
+    /// in the history.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, MoveError};
+    /// let mut board = Chessboard::new();
+    /// assert_eq!(board.move_to("e4").unwrap().san(), "e4");
+    /// assert_eq!(board.move_to("e4").unwrap_err(), MoveError::IllegalMove);
+    /// ```
+    pub fn move_to(&mut self, san: &str) -> Result<&ChessMove, MoveError> {
+        self.play_san(san)?;
+
+        Ok(self.history.last().unwrap())
+    }
+
+    /// Returns the board after the move, leaving this board untouched.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// let preview = board.preview("e4").unwrap();
+    /// assert_eq!(preview.get_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+    /// ```
+    pub fn preview(&self, san: &str) -> Result<Chessboard, MoveError> {
+        let mut board = self.clone();
+        board.play_san(san)?;
+
+        Ok(board)
+    }
+
+    /// Returns if the move would put the enemy king in check, whether directly, by uncovering
+    /// another piece or through the promoted piece. Illegal moves never give check.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::from_fen("4k3/8/8/8/4N3/8/8/4R1K1 w - - 0 1");
+    /// assert!(board.gives_check(Square::E4, Square::C3, None));
+    /// ```
+    pub fn gives_check(&self, from: Square, to: Square, promotion: Option<Piece>) -> bool {
+        let info = match self
+            .legal_move_list()
+            .into_iter()
+            .find(|info| info.from == from && info.to == to && info.promotion == promotion)
+        {
+            Some(info) => info,
+            None => return false,
+        };
+
+        let mut board = self.clone();
+        board.apply_move_info(&info, self.san_without_suffix(&info)) && board.is_checked()
+    }
+
+    /// Returns every legal move that checkmates the opponent, in the order of `legal_move_list`.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    /// let mates = board.find_mate_in_one();
+    /// assert_eq!(mates.len(), 1);
+    /// assert_eq!(mates[0].to, Square::A8);
+    /// ```
+    pub fn find_mate_in_one(&self) -> Vec<MoveInfo> {
+        self.legal_move_list()
+            .into_iter()
+            .filter(|info| {
+                let mut board = self.clone();
+                board.apply_move_info(info, self.san_without_suffix(info)) && board.is_mate()
+            })
+            .collect()
+    }
+
+    /// Moves a piece in SAN and returns the canonical SAN of the move that was played, with only
+    /// the disambiguation it needs and its check or mate suffix. The history records the
+    /// canonical SAN as well.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// assert_eq!(board.play("Ng1f3").unwrap(), "Nf3");
+    /// ```
+    pub fn play(&mut self, san: &str) -> Result<String, MoveError> {
+        let before = self.clone();
+        self.play_san(san)?;
+
+        let last_move = self.history.last().unwrap();
+        let mut canonical = before.san_without_suffix(&last_move.to_move_info());
+        canonical.push_str(self.check_suffix());
+
+        self.history.last_mut().unwrap().set_san(canonical.clone());
+
+        Ok(canonical)
+    }
+
+    /// Moves a piece in SAN and returns how the game ended, or `None` if it goes on.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Termination};
+    /// let mut board = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    /// assert_eq!(board.play_and_check("Ra8").unwrap(), Some(Termination::Checkmate));
+    /// ```
+    pub fn play_and_check(&mut self, san: &str) -> Result<Option<Termination>, MoveError> {
+        self.play_san(san)?;
+
+        Ok(self.termination())
+    }
+
+    /// Sets whether a pawn moved to the last rank in SAN without a promotion piece, such as
+    /// `e8`, promotes to a queen. When off, such moves fail with `MoveError::PromotionRequired`.
+    /// Auto-queen is on by default.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, MoveError};
+    /// let mut board = Chessboard::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1");
+    /// board.set_auto_queen(false);
+    /// assert_eq!(board.play("e8"), Err(MoveError::PromotionRequired));
+    /// assert_eq!(board.play("e8=N").unwrap(), "e8=N");
+    /// ```
+    pub fn set_auto_queen(&mut self, on: bool) {
+        self.auto_queen = on;
+    }
+
+    fn parse_san(&self, san: &str) -> Result<SanMove, MoveError> {
+        let mut valid_san = SanMove::parse_with_auto_queen(san, self.auto_queen)?;
+
+        // A pawn move without an origin file, such as "d8=Q", is a push along its own file when
+        // one is possible.
+        if valid_san.piece == Piece::PAWN && valid_san.from == 0 && valid_san.to != 0 {
+            let file = Square::from(valid_san.to).file_mask();
+            let pawns = self.pieces[Piece::PAWN as usize] & self.get_color(self.turn) & file;
+
+            if Self::get_squares(pawns)
+                .into_iter()
+                .any(|from| self.legal_moves.get(&from).copied().unwrap_or(0) & valid_san.to != 0)
+            {
+                valid_san.from = file;
+            }
+        }
+
+        if valid_san.piece == Piece::PAWN
+            && valid_san.to & (RANK_1 | RANK_8) != 0
+            && valid_san.promotion.is_none()
+        {
+            return Err(MoveError::PromotionRequired);
+        }
+
+        Ok(valid_san)
+    }
+
+    /// Returns the squares of the pieces of the side to move that the SAN could refer to.
+    /// Castling has a single origin and isn't counted.
+    fn san_origins(&self, valid_san: &SanMove) -> u64 {
+        if valid_san.castling.is_some() || valid_san.piece == Piece::UNKNOWN {
+            return 0;
+        }
+
+        let mut origins = self.pieces[valid_san.piece as usize] & self.get_color(self.turn);
+        if valid_san.from != 0 {
+            origins &= valid_san.from;
+        }
+
+        Self::get_squares(origins)
+            .into_iter()
+            .filter(|from| self.legal_moves.get(from).copied().unwrap_or(0) & valid_san.to != 0)
+            .fold(0, |origins, from| origins | from)
+    }
+
+    pub(crate) fn play_san(&mut self, san: &str) -> Result<(), MoveError> {
+        let mut valid_san = self.parse_san(san)?;
+
+        if self.san_origins(&valid_san).count_ones() > 1 {
+            return Err(MoveError::AmbiguousMove);
+        }
+
+        match self.apply_san(&mut valid_san) {
+            true => Ok(()),
+            false => Err(MoveError::IllegalMove),
+        }
+    }
+
+    /// Moves a piece in UCI long algebraic notation, e.g. `e2e4` or `e7e8q`. Castling is written
+    /// as the king's move, such as `e1g1`, and the history records the move in canonical SAN.
+    /// Unlike SAN input, a pawn reaching the last rank must name its promotion piece,
+    /// otherwise `MoveError::PromotionRequired` is returned.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// board.make_move_uci("e2e4").unwrap();
+    /// assert_eq!(board.get_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+    /// ```
+    pub fn make_move_uci(&mut self, uci: &str) -> Result<(), MoveError> {
+        if !uci.is_ascii() || (uci.len() != 4 && uci.len() != 5) {
+            return Err(MoveError::InvalidUci);
+        }
+
+        let from = Square::from(&uci[0..2]);
+        let to = Square::from(&uci[2..4]);
+
+        if from == Square::UNKNOWN || to == Square::UNKNOWN {
+            return Err(MoveError::InvalidUci);
+        }
+
+        let promotion = match uci[4..].chars().next() {
+            Some('q') => Some(Piece::QUEEN),
+            Some('r') => Some(Piece::ROOK),
+            Some('b') => Some(Piece::BISHOP),
+            Some('n') => Some(Piece::KNIGHT),
+            Some(_) => return Err(MoveError::InvalidUci),
+            None => None,
+        };
+
+        let from: u64 = from.into();
+        let to: u64 = to.into();
+
+        if from & self.get_color(self.turn) == 0 {
+            return Err(MoveError::IllegalMove);
+        }
+
+        let piece = self.get_piece(from);
+
+        if piece == Piece::PAWN && to & (RANK_1 | RANK_8) != 0 && promotion.is_none() {
+            return Err(MoveError::PromotionRequired);
+        }
+
+        let info = self
+            .legal_move_list()
+            .into_iter()
+            .find(|info| {
+                u64::from(info.from) == from
+                    && u64::from(info.to) == to
+                    && info.promotion == promotion
+            })
+            .ok_or(MoveError::IllegalMove)?;
+
+        let mut san = self.san_without_suffix(&info);
+        self.apply_move_info(&info, san.clone());
+
+        san.push_str(self.check_suffix());
+        self.history.last_mut().unwrap().set_san(san);
+
+        Ok(())
+    }
+
+    fn apply_san(&mut self, valid_san: &mut SanMove) -> bool {
+        let mut has_moved = false;
+        let castle_rights = self.castle_rights;
+        let en_passant_key = self.en_passant_zobrist_key();
+        let (white, black, pieces) = (self.white, self.black, self.pieces);
+        let to_square = valid_san.to;
+        if valid_san.piece == Piece::KING
+            && ((self.turn
+                && self.castle_rights[0]
+                && to_square & WHITE_KING_SIDE_CASTLE_SQUARE != 0)
+                || (!self.turn
+                    && self.castle_rights[2]
+                    && to_square & BLACK_KING_SIDE_CASTLE_SQUARE != 0))
+            || valid_san.castling == Some(CastlingType::KingSide)
+        {
+            valid_san.castling = Some(CastlingType::KingSide);
+            let (king, rook) = Self::castling_squares(CastlingType::KingSide, self.turn);
+            has_moved = self.castle(valid_san, king, rook);
+        } else if valid_san.piece == Piece::KING
+            && ((self.turn
+                && self.castle_rights[1]
+                && to_square & WHITE_QUEEN_SIDE_CASTLE_SQUARE != 0)
+                || (!self.turn
+                    && self.castle_rights[3]
+                    && to_square & BLACK_QUEEN_SIDE_CASTLE_SQUARE != 0))
+            || valid_san.castling == Some(CastlingType::QueenSide)
+        {
+            valid_san.castling = Some(CastlingType::QueenSide);
+            let (king, rook) = Self::castling_squares(CastlingType::QueenSide, self.turn);
+            has_moved = self.castle(valid_san, king, rook);
+        } else if let Some(promotion_piece) = valid_san.promotion {
+            'search: for (&from_square, &legal_moves) in self.legal_moves.iter() {
+                let piece = self.get_piece(from_square);
+                let color = self.white & from_square != 0;
+
+                if self.turn != color
+                    || (piece != valid_san.piece)
+                    || (valid_san.from > 0 && from_square & valid_san.from == 0)
+                {
+                    continue;
+                }
+
+                match piece {
+                    Piece::PAWN => {
+                        for valid_square in Chessboard::get_squares(legal_moves) {
+                            if valid_square & to_square != 0 {
+                                let before = self.get_fen();
+                                self.en_passant_square = None;
+                                let mut captured = None;
+                                let mut captured_square = None;
+
+                                self.pieces[piece as usize] ^= from_square;
+                                self.toggle_zobrist_piece(piece, color, from_square);
+
+                                match color {
+                                    true => {
+                                        self.white ^= from_square;
+                                        self.white |= valid_square;
+
+                                        if self.black & valid_square != 0 {
+                                            let captured_piece = self.get_piece(valid_square);
+                                            self.pieces[captured_piece as usize] ^= valid_square;
+                                            self.black ^= valid_square;
+                                            self.toggle_zobrist_piece(
+                                                captured_piece,
+                                                false,
+                                                valid_square,
+                                            );
+                                            captured = Some(captured_piece);
+                                            captured_square = Some(valid_square);
+                                        }
+                                    }
+                                    false => {
+                                        self.black ^= from_square;
+                                        self.black |= valid_square;
+
+                                        if self.white & valid_square != 0 {
+                                            let captured_piece = self.get_piece(valid_square);
+                                            self.pieces[captured_piece as usize] ^= valid_square;
+                                            self.white ^= valid_square;
+                                            self.toggle_zobrist_piece(
+                                                captured_piece,
+                                                true,
+                                                valid_square,
+                                            );
+                                            captured = Some(captured_piece);
+                                            captured_square = Some(valid_square);
+                                        }
+                                    }
+                                }
+
+                                self.pieces[promotion_piece as usize] |= valid_square;
+                                self.toggle_zobrist_piece(promotion_piece, color, valid_square);
+
+                                self.update_clocks(true);
+
+                                let after = self.get_fen();
+
+                                self.history.push(ChessMove::new(
+                                    valid_san,
+                                    self.turn,
+                                    before,
+                                    after,
+                                    from_square,
+                                    captured,
+                                    captured_square,
+                                ));
+
+                                self.turn = !self.turn;
+                                has_moved = true;
+                                break 'search;
+                            }
+                        }
+                    }
+                    _ => {
+                        continue;
+                    }
+                }
+            }
+        } else {
+            'search: for (&from_square, &legal_moves) in self.legal_moves.iter() {
+                let piece = self.get_piece(from_square);
+                let color = self.white & from_square != 0;
+
+                if self.turn != color
+                    || (piece != valid_san.piece)
+                    || (valid_san.from > 0 && from_square & valid_san.from == 0)
+                {
+                    continue;
+                }
+
+                for valid_square in Chessboard::get_squares(legal_moves) {
+                    if valid_square & to_square != 0 {
+                        let before = self.get_fen();
+                        let mut captured = None;
+                        let mut captured_square = None;
+
+                        if let Some(en_passant_square) = self.en_passant_square.take() {
+                            if valid_square == en_passant_square && piece == Piece::PAWN {
+                                self.pieces[Piece::PAWN as usize] ^= en_passant_square;
+                                let pawn_square = match self.turn {
+                                    true => en_passant_square >> 8,
+                                    false => en_passant_square << 8,
+                                };
+                                match self.turn {
+                                    true => self.black ^= pawn_square,
+                                    false => self.white ^= pawn_square,
+                                }
+                                self.pieces[Piece::PAWN as usize] ^= pawn_square;
+                                self.toggle_zobrist_piece(Piece::PAWN, !self.turn, pawn_square);
+                                captured = Some(Piece::PAWN);
+                                captured_square = Some(pawn_square);
+                            }
+                        }
+
+                        match piece {
+                            Piece::KING => match self.turn {
+                                true => {
+                                    self.castle_rights[0] = false;
+                                    self.castle_rights[1] = false;
+                                }
+                                false => {
+                                    self.castle_rights[2] = false;
+                                    self.castle_rights[3] = false;
+                                }
+                            },
+                            Piece::ROOK => match self.turn {
+                                true => {
+                                    let h1: u64 = Square::H1.into();
+                                    let a1: u64 = Square::A1.into();
+                                    if from_square & h1 != 0 {
+                                        self.castle_rights[0] = false;
+                                    } else if from_square & a1 != 0 {
+                                        self.castle_rights[1] = false;
+                                    }
+                                }
+                                false => {
+                                    let h8: u64 = Square::H8.into();
+                                    let a8: u64 = Square::A8.into();
+                                    if from_square & h8 != 0 {
+                                        self.castle_rights[2] = false;
+                                    } else if from_square & a8 != 0 {
+                                        self.castle_rights[3] = false;
+                                    }
+                                }
+                            },
+                            Piece::PAWN => match self.turn {
+                                true => {
+                                    if from_square & RANK_2 != 0 && valid_square & RANK_4 != 0 {
+                                        let black_pawns =
+                                            self.pieces[Piece::PAWN as usize] & self.black;
+                                        if valid_square & FILE_A == 0
+                                            && black_pawns & (valid_square >> 1) != 0
+                                        {
+                                            self.en_passant_square = Some(valid_square >> 8);
+                                        }
+
+                                        if valid_square & FILE_H == 0
+                                            && black_pawns & (valid_square << 1) != 0
+                                        {
+                                            self.en_passant_square = Some(valid_square >> 8);
+                                        }
+                                    }
+                                }
+                                false => {
+                                    if from_square & RANK_7 != 0 && valid_square & RANK_5 != 0 {
+                                        let white_pawns =
+                                            self.pieces[Piece::PAWN as usize] & self.white;
+                                        if valid_square & FILE_A == 0
+                                            && white_pawns & (valid_square >> 1) != 0
+                                        {
+                                            self.en_passant_square = Some(valid_square << 8);
+                                        }
+
+                                        if valid_square & FILE_H == 0
+                                            && white_pawns & (valid_square << 1) != 0
+                                        {
+                                            self.en_passant_square = Some(valid_square << 8);
+                                        }
+                                    }
+                                }
+                            },
+                            _ => {}
+                        }
+
+                        self.pieces[piece as usize] ^= from_square;
+                        self.toggle_zobrist_piece(piece, self.turn, from_square);
+
+                        match self.turn {
+                            true => {
+                                self.white ^= from_square;
+                                self.white |= valid_square;
+
+                                if self.black & valid_square != 0 {
+                                    let captured_piece = self.get_piece(valid_square);
+                                    self.pieces[captured_piece as usize] ^= valid_square;
+                                    self.black ^= valid_square;
+                                    self.toggle_zobrist_piece(captured_piece, false, valid_square);
+                                    captured = Some(captured_piece);
+                                    captured_square = Some(valid_square);
+                                }
+                            }
+                            false => {
+                                self.black ^= from_square;
+                                self.black |= valid_square;
+
+                                if self.white & valid_square != 0 {
+                                    let captured_piece = self.get_piece(valid_square);
+                                    self.pieces[captured_piece as usize] ^= valid_square;
+                                    self.white ^= valid_square;
+                                    self.toggle_zobrist_piece(captured_piece, true, valid_square);
+                                    captured = Some(captured_piece);
+                                    captured_square = Some(valid_square);
+                                }
+                            }
+                        }
+
+                        self.update_clocks(piece == Piece::PAWN || captured.is_some());
+
+                        self.pieces[piece as usize] |= valid_square;
+                        self.toggle_zobrist_piece(piece, self.turn, valid_square);
+                        let after = self.get_fen();
+
+                        self.history.push(ChessMove::new(
+                            valid_san,
+                            self.turn,
+                            before,
+                            after,
+                            from_square,
+                            captured,
+                            captured_square,
+                        ));
+
+                        self.turn = !self.turn;
+                        has_moved = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+
+        if has_moved {
+            self.zobrist ^= en_passant_key ^ zobrist::turn_key();
+            for (index, &castle_right) in castle_rights.iter().enumerate() {
+                if castle_right != self.castle_rights[index] {
+                    self.zobrist ^= zobrist::castling_key(index);
+                }
+            }
+
+            self.redo.clear();
+            self.update_static_attack_masks(white, black, pieces);
+            self.generate_legal_moves();
+            self.zobrist ^= self.en_passant_zobrist_key();
+            self.position_keys.push(self.zobrist);
+        }
+
+        has_moved
+    }
+
+    /// Returns the SAN of the moves played from the ply onwards, counting from 0. A ply past the
+    /// end of the history returns no moves.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen_with_moves("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &["e4", "e5", "Nf3"]);
+    /// assert_eq!(board.moves_since(1), vec!["e5", "Nf3"]);
+    /// ```
+    pub fn moves_since(&self, ply: usize) -> Vec<String> {
+        self.history
+            .iter()
+            .skip(ply)
+            .map(|chess_move| chess_move.san().to_string())
+            .collect()
+    }
+
+    /// Returns the FEN of the position before the last move, or `None` if no move has been
+    /// played. The board itself isn't changed.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// assert_eq!(board.previous_fen(), None);
+    /// board.move_to("e4").unwrap();
+    /// assert_eq!(board.previous_fen().unwrap(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    /// ```
+    pub fn previous_fen(&self) -> Option<String> {
+        self.history
+            .last()
+            .map(|last_move| last_move.before().to_string())
+    }
+
+    /// Reverts the last move and returns it, or `None` if no move has been played.
+    /// Any moves stepped back over with `step_back` are discarded.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// board.move_to("e4").unwrap();
+    /// board.undo_move();
+    /// assert_eq!(board.get_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    /// ```
+    pub fn undo_move(&mut self) -> Option<ChessMove> {
+        let last_move = self.take_back()?;
+        self.redo.clear();
+
+        Some(last_move)
+    }
+
+    fn take_back(&mut self) -> Option<ChessMove> {
+        let last_move = self.history.pop()?;
+
+        let mut board = Self::load_fen(last_move.before());
+        board.history = std::mem::take(&mut self.history);
+        board.position_keys = std::mem::take(&mut self.position_keys);
+        board.position_keys.pop();
+        board.redo = std::mem::take(&mut self.redo);
+        board.auto_queen = self.auto_queen;
+
+        *self = board;
+        self.generate_legal_moves();
+        self.zobrist = self.zobrist_key();
+
+        Some(last_move)
+    }
+
+    /// Returns the number of moves played up to the current position in the history.
+    pub fn cursor(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Steps back one move in the history, keeping it so it can be replayed with `step_forward`.
+    /// Returns `false` at the start of the game. Playing a new move discards the moves stepped over.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// board.move_to("e4").unwrap();
+    /// assert!(board.step_back());
+    /// assert!(!board.step_back());
+    /// assert!(board.step_forward());
+    /// assert_eq!(board.cursor(), 1);
+    /// ```
+    pub fn step_back(&mut self) -> bool {
+        match self.take_back() {
+            Some(last_move) => {
+                self.redo.push(last_move);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replays the next move stepped back over with `step_back`.
+    /// Returns `false` when there is no such move.
+    pub fn step_forward(&mut self) -> bool {
+        let mut redo = std::mem::take(&mut self.redo);
+
+        let has_moved = match redo.pop() {
+            Some(next_move) => self.apply_san(&mut next_move.to_san_move()),
+            None => false,
+        };
+
+        self.redo = redo;
+        has_moved
+    }
+
+    fn castle(
+        &mut self,
+        valid_san: &mut SanMove,
+        (king_from, king_to): (Square, Square),
+        (rook_from, rook_to): (Square, Square),
+    ) -> bool {
+        let king_from: u64 = king_from.into();
+        let king_to: u64 = king_to.into();
+        let rook_from: u64 = rook_from.into();
+        let rook_to: u64 = rook_to.into();
+
+        match self.legal_moves.get(&king_from) {
+            Some(&legal_moves) if legal_moves & king_to != 0 => {}
+            _ => return false,
+        }
+
+        let before = self.get_fen();
+        self.en_passant_square = None;
+        self.update_clocks(false);
+
+        self.pieces[Piece::KING as usize] ^= king_from;
+        self.pieces[Piece::KING as usize] |= king_to;
+        self.pieces[Piece::ROOK as usize] ^= rook_from;
+        self.pieces[Piece::ROOK as usize] |= rook_to;
+        for (piece, square) in [
+            (Piece::KING, king_from),
+            (Piece::KING, king_to),
+            (Piece::ROOK, rook_from),
+            (Piece::ROOK, rook_to),
+        ] {
+            self.toggle_zobrist_piece(piece, self.turn, square);
+        }
+
+        match self.turn {
+            true => {
+                self.white ^= king_from | rook_from;
+                self.white |= king_to | rook_to;
+                self.castle_rights[0] = false;
+                self.castle_rights[1] = false;
+            }
+            false => {
+                self.black ^= king_from | rook_from;
+                self.black |= king_to | rook_to;
+                self.castle_rights[2] = false;
+                self.castle_rights[3] = false;
+            }
+        }
+
+        valid_san.to = king_to;
+        let after = self.get_fen();
+
+        self.history.push(ChessMove::new(
+            valid_san, self.turn, before, after, king_from, None, None,
+        ));
+
+        self.turn = !self.turn;
+        true
+    }
+
+    fn update_clocks(&mut self, is_pawn_or_capture: bool) {
+        match is_pawn_or_capture {
+            true => self.half_move = 0,
+            false => self.half_move += 1,
+        }
+
+        if !self.turn {
+            self.full_move += 1;
+        }
+    }
+
+    fn get_squares(bitboard: u64) -> Vec<u64> {
+        let mut squares = Vec::new();
+        for i in 0..64 {
+            let square = 1 << i;
+            if bitboard & square != 0 {
+                squares.push(square);
+            }
+        }
+        squares
+    }
+
+    /// Returns a snapshot of the side to move, castling rights, en passant square and move counters.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// let snapshot = board.state_snapshot();
+    /// board.restore_state(snapshot);
+    /// ```
+    pub fn state_snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            turn: self.turn,
+            castle_rights: self.castle_rights,
+            en_passant_square: self.en_passant_square,
+            half_move: self.half_move,
+            full_move: self.full_move,
+        }
+    }
+
+    /// Restores the side to move, castling rights, en passant square and move counters from a
+    /// snapshot. The pieces are left untouched, so castling rights and an en passant square that
+    /// don't match them are dropped, as in `from_fen_checked`.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    /// let snapshot = board.state_snapshot();
+    /// for san in ["Ra2", "Ra7", "Ra1", "Ra8"] {
+    ///     board.move_to(san).unwrap();
+    /// }
+    /// assert_eq!(board.get_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w Kk - 4 3");
+    /// board.restore_state(snapshot);
+    /// assert_eq!(board.get_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    /// ```
+    pub fn restore_state(&mut self, snapshot: StateSnapshot) {
+        self.turn = snapshot.turn;
+        self.castle_rights = snapshot.castle_rights;
+        self.en_passant_square = snapshot.en_passant_square;
+        self.half_move = snapshot.half_move;
+        self.full_move = snapshot.full_move;
+        self.sanitize();
+
+        self.generate_legal_moves();
+        self.zobrist = self.zobrist_key();
+    }
+
+    /// Regenerates the legal moves of the current position `iterations` times and returns the
+    /// elapsed time. This is a crude way to track the cost of move generation.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// let elapsed = board.bench_movegen(10);
+    /// println!("{:?}", elapsed);
+    /// ```
+    pub fn bench_movegen(&mut self, iterations: u32) -> std::time::Duration {
+        let start = std::time::Instant::now();
+
+        for _ in 0..iterations {
+            self.generate_legal_moves();
+        }
+
+        start.elapsed()
+    }
+
+    /// Returns the ASCII representation of the current position.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// println!("{}", board.ascii());
+    /// ```
+    pub fn ascii(&self) -> String {
+        let mut board = String::new();
+        for rank in 0_u64..8 {
+            for file in 0_u64..8 {
+                let square = 1 << (56 - rank * 8 + file);
+                let piece = self.get_piece(square);
+
+                if square & self.all() != 0 {
+                    let color = self.white & square != 0;
+                    match piece {
+                        Piece::PAWN => match color {
+                            true => {
+                                board.push('\u{2659}');
+                            }
+                            false => {
+                                board.push('\u{265F}');
+                            }
+                        },
+                        Piece::KNIGHT => match color {
+                            true => {
+                                board.push('\u{2658}');
+                            }
+                            false => {
+                                board.push('\u{265E}');
+                            }
+                        },
+                        Piece::BISHOP => match color {
+                            true => {
+                                board.push('\u{2657}');
+                            }
+                            false => {
+                                board.push('\u{265D}');
+                            }
+                        },
+                        Piece::ROOK => match color {
+                            true => {
+                                board.push('\u{2656}');
+                            }
+                            false => {
+                                board.push('\u{265C}');
+                            }
+                        },
+                        Piece::QUEEN => match color {
+                            true => {
+                                board.push('\u{2655}');
+                            }
+                            false => {
+                                board.push('\u{265B}');
+                            }
+                        },
+                        Piece::KING => match color {
+                            true => {
+                                board.push('\u{2654}');
+                            }
+                            false => {
+                                board.push('\u{265A}');
+                            }
+                        },
+                        _ => {}
+                    }
+                } else {
+                    board.push('\u{25A1}');
+                }
+                board.push(' ');
+            }
+
+            board.push('\n');
+        }
+        board
+    }
+
+    /// Returns the legal moves of the current position with their origin, destination, moving
+    /// piece, captured piece and promotion. A promoting pawn move is listed once for each
+    /// promotion piece.
+    ///
+    /// The moves are sorted by origin square, then destination square (both from a1 to h8), then
+    /// promotion piece in `Piece` order, so identical positions always list their moves the same way.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.legal_move_list().len(), 20);
+    /// ```
+    pub fn legal_move_list(&self) -> Vec<MoveInfo> {
+        let mut move_list = Vec::new();
+
+        for from in Self::get_squares(self.get_color(self.turn)) {
+            let moves = match self.legal_moves.get(&from) {
+                Some(&moves) => moves,
+                None => continue,
+            };
+            let piece = self.get_piece(from);
+
+            for to in Self::get_squares(moves) {
+                let captured_square = self.get_captured_square(from, to);
+                let captured = match captured_square {
+                    0 => None,
+                    _ => Some(self.get_piece(captured_square)),
+                };
+
+                let castling = match piece {
+                    Piece::KING if to == from << 2 => Some(CastlingType::KingSide),
+                    Piece::KING if to == from >> 2 => Some(CastlingType::QueenSide),
+                    _ => None,
+                };
+
+                let mut info = MoveInfo {
+                    from: Square::from(from),
+                    to: Square::from(to),
+                    piece,
+                    captured,
+                    promotion: None,
+                    castling,
+                    en_passant: piece == Piece::PAWN && self.en_passant_square == Some(to),
+                };
+
+                if piece == Piece::PAWN && to & (RANK_1 | RANK_8) != 0 {
+                    for promotion in [Piece::QUEEN, Piece::ROOK, Piece::BISHOP, Piece::KNIGHT] {
+                        info.promotion = Some(promotion);
+                        move_list.push(info);
+                    }
+                } else {
+                    move_list.push(info);
+                }
+            }
+        }
+
+        move_list.sort_by_key(|info| {
+            (
+                info.from as u8,
+                info.to as u8,
+                info.promotion.map(|promotion| promotion as u8),
+            )
+        });
+
+        move_list
+    }
+
+    /// Returns the legal moves of the color as if it were to move, without changing the board.
+    /// For the side not to move there is no en passant capture, since only the side to move can
+    /// capture en passant.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Color};
+    /// let mut board = Chessboard::new();
+    /// board.move_to("e4").unwrap();
+    /// assert_eq!(board.moves_for_color(Color::White).len(), 30);
+    /// ```
+    pub fn moves_for_color(&self, color: Color) -> Vec<MoveInfo> {
+        let color: bool = color.into();
+
+        if color == self.turn {
+            return self.legal_move_list();
+        }
+
+        let mut board = self.clone();
+        board.turn = color;
+        board.en_passant_square = None;
+        board.generate_legal_moves();
+
+        board.legal_move_list()
+    }
+
+    /// Returns the legal moves that capture a piece, including en passant, in the order of
+    /// `legal_move_list`.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+    /// assert_eq!(board.capture_moves().len(), 1);
+    /// ```
+    pub fn capture_moves(&self) -> Vec<MoveInfo> {
+        self.legal_move_list()
+            .into_iter()
+            .filter(|info| info.captured.is_some())
+            .collect()
+    }
+
+    /// Returns the legal moves that don't capture a piece, including castling, in the order of
+    /// `legal_move_list`.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.quiet_moves().len(), 20);
+    /// ```
+    pub fn quiet_moves(&self) -> Vec<MoveInfo> {
+        self.legal_move_list()
+            .into_iter()
+            .filter(|info| info.captured.is_none())
+            .collect()
+    }
+
+    /// Returns the legal moves whose destination is one of the target squares, in the order of
+    /// `legal_move_list`.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Square};
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.legal_moves_to(Square::F3.into()).len(), 2);
+    /// ```
+    pub fn legal_moves_to(&self, targets: u64) -> Vec<MoveInfo> {
+        self.legal_move_list()
+            .into_iter()
+            .filter(|info| u64::from(info.to) & targets != 0)
+            .collect()
+    }
+
+    /// Returns the legal moves that don't end the game in a draw right away, whether by
+    /// threefold repetition, the fifty move rule, insufficient material or stalemate.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1");
+    /// assert_eq!(board.legal_move_count(), 5);
+    /// assert_eq!(board.non_drawing_moves().len(), 4);
+    /// ```
+    pub fn non_drawing_moves(&self) -> Vec<MoveInfo> {
+        self.legal_move_list()
+            .into_iter()
+            .filter(|info| {
+                let mut board = self.clone();
+                board.apply_move_info(info, self.san_without_suffix(info));
+                board.result() != Some(GameResult::Draw)
+            })
+            .collect()
+    }
+
+    /// Returns each legal move in SAN along with the FEN of the position it leads to.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// let successors = board.successor_positions();
+    /// assert!(successors.contains(&(
+    ///     "e4".to_string(),
+    ///     "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".to_string()
+    /// )));
+    /// ```
+    pub fn successor_positions(&self) -> Vec<(String, String)> {
+        self.legal_move_list()
+            .iter()
+            .map(|info| {
+                let mut san = self.san_without_suffix(info);
+                let mut board = self.clone();
+                board.apply_move_info(info, san.clone());
+                san.push_str(board.check_suffix());
+
+                (san, board.get_fen())
+            })
+            .collect()
+    }
+
+    /// Returns the SAN of the legal move from `from` to `to`, with only the disambiguation it
+    /// needs and its check or mate suffix, or `None` if the move isn't legal. A pawn reaching
+    /// the last rank needs its promotion piece.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Chessboard, Piece, Square};
+    /// let board = Chessboard::from_fen("3rk3/2P5/8/8/8/8/8/R3K2R w KQ - 0 1");
+    /// assert_eq!(board.to_san(Square::C7, Square::D8, Some(Piece::QUEEN)), Some("cxd8=Q+".to_string()));
+    /// assert_eq!(board.to_san(Square::E1, Square::G1, None), Some("O-O".to_string()));
+    /// assert_eq!(board.to_san(Square::A1, Square::A8, None), Some("Ra8".to_string()));
+    /// assert_eq!(board.to_san(Square::C7, Square::D8, None), None);
+    /// ```
+    pub fn to_san(&self, from: Square, to: Square, promotion: Option<Piece>) -> Option<String> {
+        self.legal_move_list()
+            .iter()
+            .find(|info| info.from == from && info.to == to && info.promotion == promotion)
+            .map(|info| self.san(info))
+    }
+
+    fn san(&self, info: &MoveInfo) -> String {
+        let mut san = self.san_without_suffix(info);
+        let mut board = self.clone();
+        board.apply_move_info(info, san.clone());
+        san.push_str(board.check_suffix());
+
+        san
+    }
+
+    fn san_without_suffix(&self, info: &MoveInfo) -> String {
+        match info.castling {
+            Some(CastlingType::KingSide) => return "O-O".to_string(),
+            Some(CastlingType::QueenSide) => return "O-O-O".to_string(),
+            None => {}
+        }
+
+        let from: u64 = info.from.into();
+        let to: u64 = info.to.into();
+        let from_name = info.from.to_string();
+        let mut san = String::new();
+
+        match info.piece {
+            Piece::PAWN => {
+                if info.captured.is_some() {
+                    san.push_str(&from_name[..1]);
+                }
+            }
+            piece => {
+                san.push(piece.san_symbol());
+
+                let rivals: Vec<u64> = Self::get_squares(
+                    self.pieces[piece as usize] & self.get_color(self.turn) & !from,
+                )
+                .into_iter()
+                .filter(|square| {
+                    self.legal_moves
+                        .get(square)
+                        .is_some_and(|&moves| moves & to != 0)
+                })
+                .collect();
+
+                if !rivals.is_empty() {
+                    let same_file = rivals
+                        .iter()
+                        .any(|&square| square.trailing_zeros() % 8 == from.trailing_zeros() % 8);
+                    let same_rank = rivals
+                        .iter()
+                        .any(|&square| square.trailing_zeros() / 8 == from.trailing_zeros() / 8);
+
+                    if !same_file {
+                        san.push_str(&from_name[..1]);
+                    } else if !same_rank {
+                        san.push_str(&from_name[1..]);
+                    } else {
+                        san.push_str(&from_name);
+                    }
+                }
+            }
+        }
+
+        if info.captured.is_some() {
+            san.push('x');
+        }
+
+        san.push_str(&info.to.to_string());
+
+        if let Some(promotion) = info.promotion {
+            san.push('=');
+            san.push(promotion.san_symbol());
+        }
+
+        san
+    }
+
+    fn check_suffix(&self) -> &'static str {
+        if self.is_mate() {
+            "#"
+        } else if self.is_checked() {
+            "+"
+        } else {
+            ""
+        }
+    }
+
+    fn apply_move_info(&mut self, info: &MoveInfo, san: String) -> bool {
+        let mut san_move = SanMove {
+            san,
+            piece: info.piece,
+            to: info.to.into(),
+            from: info.from.into(),
+            promotion: info.promotion,
+            castling: info.castling,
+        };
+
+        self.apply_san(&mut san_move)
+    }
+
+    /// Returns the number of legal moves in the current position, counting each promotion
+    /// piece separately.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.legal_move_count(), 20);
+    /// ```
+    pub fn legal_move_count(&self) -> usize {
+        self.legal_move_list().len()
+    }
+
+    /// Returns how many legal moves the opponent has after the move.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// assert_eq!(board.responses_after("e4").unwrap(), 20);
+    /// ```
+    pub fn responses_after(&self, san: &str) -> Result<usize, MoveError> {
+        Ok(self.preview(san)?.legal_move_count())
+    }
+
+    /// Returns whether the side to move has a pawn that can promote.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1");
+    /// assert!(board.has_promotion_available());
+    /// ```
+    pub fn has_promotion_available(&self) -> bool {
+        let last_rank = match self.turn {
+            true => RANK_8,
+            false => RANK_1,
+        };
+
+        Self::get_squares(self.get_color(self.turn) & self.pieces[Piece::PAWN as usize])
+            .into_iter()
+            .any(|square| {
+                self.legal_moves
+                    .get(&square)
+                    .is_some_and(|&moves| moves & last_rank != 0)
+            })
+    }
+
+    /// Returns the PGN of the game played so far. Move numbers start from the loaded position,
+    /// and a `FEN` tag is added when it isn't the standard starting position.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// board.move_to("e4").unwrap();
+    /// board.move_to("e5").unwrap();
+    /// assert_eq!(board.to_pgn(), "1. e4 e5 *");
+    /// ```
+    pub fn to_pgn(&self) -> String {
+        pgn::to_pgn(&self.history)
+    }
+
+    /// Returns the numbered movetext of the game played so far in canonical SAN, without tags,
+    /// comments or a result. Move numbers start from the loaded position.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// board.move_to("e2e4").unwrap();
+    /// board.move_to("e5").unwrap();
+    /// board.move_to("Ng1f3").unwrap();
+    /// assert_eq!(board.movetext(), "1. e4 e5 2. Nf3");
+    /// ```
+    pub fn movetext(&self) -> String {
+        let mut board = match self.history.first() {
+            Some(first_move) => Self::from_fen(first_move.before()),
+            None => return String::new(),
+        };
+
+        for chess_move in self.history.iter() {
+            let info = chess_move.to_move_info();
+            let mut san = board.san_without_suffix(&info);
+            board.apply_move_info(&info, san.clone());
+
+            san.push_str(board.check_suffix());
+            board.history.last_mut().unwrap().set_san(san);
+        }
+
+        pgn::movetext(&board.history)
+    }
+
+    /// Records the clock time remaining after the move at the ply, counted from 0 for the first
+    /// move in the history. It is exported as a `[%clk]` comment by `to_pgn`.
+    /// Plies outside the history are ignored.
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use w_chess::Chessboard;
+    /// let mut board = Chessboard::new();
+    /// board.move_to("e4").unwrap();
+    /// board.set_clock(0, Duration::from_secs(300));
+    /// assert_eq!(board.to_pgn(), "1. e4 {[%clk 0:05:00]} *");
+    /// ```
+    pub fn set_clock(&mut self, ply: usize, clock: std::time::Duration) {
+        if let Some(chess_move) = self.history.get_mut(ply) {
+            chess_move.set_clock(clock);
+        }
+    }
+
+    /// Returns the legal moves of the current position in SAN, sorted by origin square and then
+    /// target square. Each move has the disambiguation, capture and check markers it needs, so
+    /// it can be played back with `move_to`.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let board = Chessboard::new();
+    /// let legal_moves = board.legal_moves();
+    /// assert_eq!(&legal_moves[..4], ["Na3", "Nc3", "Nf3", "Nh3"]);
+    /// ```
+    pub fn legal_moves(&self) -> Vec<String> {
+        self.legal_move_list()
+            .iter()
+            .map(|info| self.san(info))
+            .collect()
+    }
+}
+
+impl Default for Chessboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for Chessboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.ascii())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_moves() {
+        let board = Chessboard::from_fen("8/4PnK1/4P3/2p3p1/1p2BPk1/P7/2pR2PB/5n2 w - - 0 1");
+
+        for (&square, &valid_move) in board.legal_moves.iter() {
+            let color: bool = board.white & square != 0;
+            if color != board.turn {
+                continue;
+            }
+            println!("{} on {}", board.get_piece(square), Square::from(square));
+            for square in Chessboard::get_squares(valid_move) {
+                println!("valid_move: {}", Square::from(square));
+            }
+        }
+    }
+
+    #[test]
+    fn get_fen_works() {
+        let fen = "8/PK4N1/P1p2rn1/7p/1P1B3P/2P5/p1NR4/5k2 w - - 0 1";
+        let board = Chessboard::from_fen(fen);
+
+        assert_eq!(board.get_fen(), fen);
+    }
+
+    #[test]
+    fn test_fen_square_mapping() {
+        let fen = "rnbqkbnr/pPpPpPpP/8/NBRQKQRB/bnrqkqrn/8/PpPpPpPp/RNBQKBNR w - - 0 1";
+        let board = Chessboard::from_fen(fen);
+        assert_eq!(board.get_fen(), fen);
+
+        let array = board.to_array();
+        for (rank, fen_rank) in fen.split(' ').next().unwrap().split('/').rev().enumerate() {
+            let mut file = 0;
+            for c in fen_rank.chars() {
+                match c.to_digit(10) {
+                    Some(empty) => {
+                        for offset in 0..empty as usize {
+                            assert_eq!(array[rank][file + offset], None);
+                        }
+                        file += empty as usize;
+                    }
+                    None => {
+                        let (piece, color) = array[rank][file].unwrap();
+                        assert_eq!(piece.san_symbol(), c.to_ascii_uppercase());
+                        assert_eq!(color == Color::White, c.is_uppercase());
+                        file += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mate() {
+        let board =
+            Chessboard::from_fen("r1bqkbnr/pppp1Qpp/8/4p3/1nB1P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 4");
+
+        assert!(board.is_mate());
+    }
+
+    #[test]
+    fn test_move_to() {
+        let mut board = Chessboard::new();
+
+        board.move_to("f4").unwrap();
+        board.move_to("f5").unwrap();
+        board.move_to("Nf3").unwrap();
+        board.move_to("Nc6").unwrap();
+        board.move_to("e4").unwrap();
+        board.move_to("e5").unwrap();
+        board.move_to("Qe2").unwrap();
+        board.move_to("Bb4").unwrap();
+        // The bishop on b4 pins the d2 pawn.
+        assert_eq!(board.move_to("d4").unwrap_err(), MoveError::IllegalMove);
+        board.move_to("c3").unwrap();
+        assert_eq!(
+            board.move_to("Nf9").unwrap_err(),
+            MoveError::InvalidSan(SanParseError::InvalidRank)
+        );
+        println!("{}", board.get_fen());
+    }
+
+    #[test]
+    fn test_edge_pawn_attacks() {
+        let board = Chessboard::from_fen("8/8/8/8/P6k/8/8/K7 b - - 0 1");
+        assert!(!board.is_checked());
+
+        let board = Chessboard::from_fen("k7/8/8/p7/8/8/7K/8 w - - 0 1");
+        assert!(board.legal_moves_map()[&Square::H2].contains(&Square::H3));
+        assert_eq!(board.attacks_from(Square::A5), u64::from(Square::B4));
+
+        let board = Chessboard::from_fen("8/8/k7/8/8/7P/8/7K b - - 0 1");
+        assert!(board.legal_moves_map()[&Square::A6].contains(&Square::A5));
+        assert_eq!(board.attacks_from(Square::H3), u64::from(Square::G4));
+
+        let board = Chessboard::from_fen("8/8/8/7p/K7/8/8/7k w - - 0 1");
+        assert!(!board.is_checked());
+        assert!(board.legal_moves_map()[&Square::A4].contains(&Square::A5));
+    }
+
+    #[test]
+    fn test_to_san() {
+        let board = Chessboard::from_fen("4k3/8/8/4p3/3P4/8/8/RN2KN1R w - - 0 1");
+
+        assert_eq!(
+            board.to_san(Square::D4, Square::E5, None),
+            Some("dxe5".to_string())
+        );
+        assert_eq!(
+            board.to_san(Square::B1, Square::D2, None),
+            Some("Nbd2".to_string())
+        );
+        assert_eq!(
+            board.to_san(Square::A1, Square::A8, None),
+            Some("Ra8+".to_string())
+        );
+        assert_eq!(board.to_san(Square::D4, Square::D6, None), None);
+
+        for san in board.legal_moves() {
+            let mut played = board.clone();
+            assert_eq!(played.play(&san), Ok(san));
+        }
+    }
+
+    #[test]
+    fn test_piece_at() {
+        let board = Chessboard::from_fen("4k3/8/8/3p4/4P3/8/8/R3K3 w Q - 0 1");
+
+        assert_eq!(board.piece_at(Square::A1), Some((Piece::ROOK, true)));
+        assert_eq!(board.piece_at(Square::E4), Some((Piece::PAWN, true)));
+        assert_eq!(board.piece_at(Square::D5), Some((Piece::PAWN, false)));
+        assert_eq!(board.piece_at(Square::E8), Some((Piece::KING, false)));
+        assert_eq!(board.piece_at(Square::H8), None);
+        assert_eq!(board.piece_at(Square::UNKNOWN), None);
+
+        let occupied = (0..64)
+            .filter(|&index| board.piece_at(Square::from(1_u64 << index)).is_some())
+            .count();
+        assert_eq!(occupied, 5);
+    }
+
+    #[test]
+    fn test_move_to_ambiguous() {
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/8/RN2KN1R w - - 0 1");
+
+        assert_eq!(board.move_to("Nd2").unwrap_err(), MoveError::AmbiguousMove);
+        assert_eq!(board.move_to("Rc1").unwrap_err(), MoveError::IllegalMove);
+        assert!(board.history.is_empty());
+
+        let chess_move = board.move_to("Nbd2").unwrap();
+        assert_eq!(chess_move.from_square(), Square::B1);
+        assert_eq!(chess_move.to_square(), Square::D2);
+
+        let mut board = Chessboard::from_fen("4k3/R7/8/8/R6R/8/8/4K3 w - - 0 1");
+        assert_eq!(board.move_to("Rd4").unwrap_err(), MoveError::AmbiguousMove);
+        assert_eq!(board.move_to("Ra5").unwrap_err(), MoveError::AmbiguousMove);
+
+        let mut played = board.clone();
+        assert_eq!(played.move_to("Rhd4").unwrap().from_square(), Square::H4);
+        let mut played = board.clone();
+        assert_eq!(played.move_to("Rad4").unwrap().from_square(), Square::A4);
+        let mut played = board.clone();
+        assert_eq!(played.move_to("R7a5").unwrap().from_square(), Square::A7);
+    }
+
+    #[test]
+    fn test_from_fen_with_moves_skips_illegal() {
+        let board = Chessboard::from_fen_with_moves(START_FEN, &["e4", "e4", "e5", "Zz9", "Nf3"]);
+
+        assert_eq!(board.moves_since(0), vec!["e4", "e5", "Nf3"]);
+    }
+
+    #[test]
+    fn test_random_board() {
+        let mut board =
+            Chessboard::from_fen("3B4/R2p1P2/3p2p1/5NP1/2K1n3/1Q6/1p3R1p/2n1k3 w - - 0 1");
+
+        println!("{}", board.ascii());
+
+        board.move_to("Qe3").unwrap();
+        board.move_to("Kd1").unwrap();
+        board.move_to("Rf1").unwrap();
+        board.move_to("Kc2").unwrap();
+        board.move_to("Qe4").unwrap();
+        board.move_to("Kd2").unwrap();
+        board.move_to("Ba5").unwrap();
+
+        println!("{}", board.get_fen());
+        println!("{}", board.ascii());
+
+        assert!(board.is_mate());
+    }
+
+    #[test]
+    fn castle_white_queen_side() {
+        let fen = "rnb1kbnr/pp2pppp/8/1q6/8/8/P3PPPP/R3K1NR w KQkq - 0 1";
+        let mut board = Chessboard::from_fen(fen);
+
+        board.move_to("O-O-O").unwrap(); // or Kc1
+        println!("{}", board);
+
+        assert_eq!(
+            board.get_fen(),
+            "rnb1kbnr/pp2pppp/8/1q6/8/8/P3PPPP/2KR2NR b kq - 1 1"
+        );
+    }
+
+    #[test]
+    fn castle_white_king_side() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPP2P/RNBQK2R w KQkq - 0 1";
+        let mut board = Chessboard::from_fen(fen);
+
+        println!("{:?}", board.legal_moves());
+
+        board.move_to("O-O").unwrap(); // or Kg1
+
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPP2P/RNBQ1RK1 b kq - 1 1"
+        );
+    }
+
+    #[test]
+    fn castle_black_queen_side() {
+        let fen = "r3kbnr/p3pppp/8/8/1Q6/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1";
+        let mut board = Chessboard::from_fen(fen);
+
+        board.move_to("O-O-O").unwrap(); // Kc8
+
+        assert_eq!(
+            board.get_fen(),
+            "2kr1bnr/p3pppp/8/8/1Q6/8/PPPPPPPP/RNBQKBNR w KQ - 1 2"
+        );
+    }
+
+    #[test]
+    fn castle_black_king_side() {
+        let fen = "rnbqk2r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1";
+        let mut board = Chessboard::from_fen(fen);
+
+        board.move_to("O-O").unwrap(); // Kg8
+
+        assert_eq!(
+            board.get_fen(),
+            "rnbq1rk1/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 1 2"
+        );
+    }
+
+    #[test]
+    fn test_en_passant() {
+        let fen = "rnbqkbnr/pppp1ppp/8/8/4p3/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+        let mut board = Chessboard::from_fen(fen);
+
+        board.move_to("d4").unwrap();
+        println!("{}", board.ascii());
+        board.move_to("d3").unwrap();
+        println!("{}", board.ascii());
+        println!("{:?}", board.history);
+
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbnr/pppp1ppp/8/8/8/3p4/PPP1PPPP/RNBQKBNR w KQkq - 0 2"
+        );
+    }
+
+    #[test]
+    fn test_en_passant_captured_square() {
+        let fen = "rnbqkbnr/pppp1ppp/8/8/4p3/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let mut board = Chessboard::from_fen(fen);
+
+        board.move_to("d4").unwrap();
+        board.move_to("exd3").unwrap();
+
+        let last_move = board.history.last().unwrap();
+        assert_eq!(last_move.captured_square(), Some(Square::D4));
+    }
+
+    #[test]
+    fn test_captured_square() {
+        let mut board = Chessboard::new();
+
+        board.move_to("e4").unwrap();
+        board.move_to("d5").unwrap();
+        board.move_to("exd5").unwrap();
+
+        let last_move = board.history.last().unwrap();
+        assert_eq!(last_move.captured_square(), Some(Square::D5));
+    }
+
+    #[test]
+    fn test_capture_checking_piece() {
+        let board = Chessboard::from_fen("4k3/8/8/8/8/3n4/8/4KB2 w - - 0 1");
+        let bishop_moves = board.legal_moves[&u64::from(Square::F1)];
+        assert_eq!(bishop_moves, u64::from(Square::D3));
+
+        let board = Chessboard::from_fen("4k3/8/8/8/8/8/3p4/2B1K3 w - - 0 1");
+        let bishop_moves = board.legal_moves[&u64::from(Square::C1)];
+        assert_eq!(bishop_moves, u64::from(Square::D2));
+    }
+
+    #[test]
+    fn test_filter_legal() {
+        let board = Chessboard::from_fen("4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1");
+
+        let legal = board.filter_legal(&[
+            (Square::E2, Square::D3, None),
+            (Square::E1, Square::D1, None),
+            (Square::E1, Square::E2, None),
+            (Square::E1, Square::F2, None),
+            (Square::E7, Square::E2, None),
+            (Square::D4, Square::D5, None),
+        ]);
+
+        assert_eq!(legal, vec![false, true, false, true, false, false]);
+    }
+
+    #[test]
+    fn test_filter_legal_capture_checker() {
+        let board = Chessboard::from_fen("4k3/8/8/8/8/3n4/8/4KB2 w - - 0 1");
+
+        let legal = board.filter_legal(&[
+            (Square::F1, Square::D3, None),
+            (Square::F1, Square::E2, None),
+            (Square::E1, Square::F2, None),
+        ]);
+
+        assert_eq!(legal, vec![true, false, false]);
+    }
+
+    #[test]
+    fn test_filter_legal_promotion() {
+        let board = Chessboard::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1");
+
+        let legal = board.filter_legal(&[
+            (Square::B7, Square::B8, Some(Piece::KNIGHT)),
+            (Square::B7, Square::B8, None),
+            (Square::B7, Square::B8, Some(Piece::KING)),
+            (Square::E1, Square::E2, Some(Piece::QUEEN)),
+        ]);
+
+        assert_eq!(legal, vec![true, false, false, false]);
+    }
+
+    #[test]
+    fn test_half_move_clock() {
+        let mut board = Chessboard::new();
+
+        board.move_to("Nf3").unwrap();
+        board.move_to("Nf6").unwrap();
+        board.move_to("Ng1").unwrap();
+        board.move_to("Ng8").unwrap();
+
+        assert_eq!(board.half_move, 4);
+
+        board.move_to("e4").unwrap();
+        assert_eq!(board.half_move, 0);
+
+        board.move_to("Nf6").unwrap();
+        board.move_to("Nc3").unwrap();
+        assert_eq!(board.half_move, 2);
+
+        board.move_to("Nxe4").unwrap();
+        assert_eq!(board.half_move, 0);
+    }
+
+    #[test]
+    fn test_half_move_clock_castling_and_promotion() {
+        let mut board = Chessboard::from_fen("4k3/1P6/8/8/8/8/8/4K2R w K - 7 40");
+
+        board.move_to("O-O").unwrap();
+        assert_eq!(board.half_move, 8);
+
+        board.move_to("Kd7").unwrap();
+        board.move_to("b8=Q").unwrap();
+        assert_eq!(board.half_move, 0);
+    }
+
+    #[test]
+    fn test_from_fen_checked() {
+        let (board, warnings) = Chessboard::from_fen_checked(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        )
+        .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+
+        let (board, warnings) = Chessboard::from_fen_checked(
+            "rnbqkbn1/pppppppr/8/8/8/8/PPPPPPPP/RNBQ1KNR w KQkq e6 0 1",
+        )
+        .unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                FenWarning::CastlingRightRemoved('K'),
+                FenWarning::CastlingRightRemoved('Q'),
+                FenWarning::CastlingRightRemoved('k'),
+                FenWarning::EnPassantRemoved(Square::E6),
+            ]
+        );
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbn1/pppppppr/8/8/8/8/PPPPPPPP/RNBQ1KNR w q - 0 1"
+        );
+
+        let (board, warnings) = Chessboard::from_fen_checked(
+            "rnbqkbnr/pppp1ppp/8/3Pp3/8/8/PPP1PPPP/RNBQKBNR w KQkq e6 0 3",
+        )
+        .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(board.en_passant_square, Some(Square::E6.into()));
+
+        assert_eq!(
+            Chessboard::from_fen_checked("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq")
+                .err(),
+            Some(FenError::MissingFields)
+        );
+    }
+
+    #[test]
+    fn test_make_move_uci() {
+        let mut board = Chessboard::new();
+
+        board.make_move_uci("e2e4").unwrap();
+        board.make_move_uci("e7e5").unwrap();
+        board.make_move_uci("g1f3").unwrap();
+
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+
+        assert_eq!(board.make_move_uci("e2e4"), Err(MoveError::IllegalMove));
+        assert_eq!(board.make_move_uci("e5e4"), Err(MoveError::IllegalMove));
+        assert_eq!(board.make_move_uci("e5"), Err(MoveError::InvalidUci));
+        assert_eq!(board.make_move_uci("e7e9"), Err(MoveError::InvalidUci));
+        assert_eq!(board.make_move_uci("d7d5x"), Err(MoveError::InvalidUci));
+    }
+
+    #[test]
+    fn test_make_move_uci_castling() {
+        let mut board = Chessboard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+        board.make_move_uci("e1g1").unwrap();
+        board.make_move_uci("e8c8").unwrap();
+        assert_eq!(board.get_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+        assert_eq!(board.history[0].san(), "O-O");
+        assert_eq!(board.history[1].san(), "O-O-O");
+
+        assert_eq!(board.make_move_uci("e1c1"), Err(MoveError::IllegalMove));
+        assert_eq!(board.make_move_uci("d4d5"), Err(MoveError::IllegalMove));
+    }
+
+    #[test]
+    fn test_make_move_uci_promotion() {
+        let fen = "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1";
+        let mut board = Chessboard::from_fen(fen);
+
+        assert_eq!(
+            board.make_move_uci("b7b8"),
+            Err(MoveError::PromotionRequired)
+        );
+        assert_eq!(board.get_fen(), fen);
+
+        board.make_move_uci("b7b8n").unwrap();
+        assert_eq!(board.get_fen(), "1N2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn test_threefold() {
+        let mut board = Chessboard::new();
+
+        board.move_to("Nf3").unwrap();
+        board.move_to("Nf6").unwrap();
+
+        board.move_to("Ng1").unwrap();
+        board.move_to("Ng8").unwrap();
+
+        board.move_to("Nf3").unwrap();
+        board.move_to("Nf6").unwrap();
+
+        board.move_to("Ng1").unwrap();
+        board.move_to("Ng8").unwrap();
+
+        assert!(board.is_threefold_repetition());
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 8 5"
+        );
+    }
+
+    #[test]
+    fn test_legal_moves_from() {
+        let board = Chessboard::from_fen("r3k3/8/8/8/8/8/8/R3K2R w KQq - 0 1");
+
+        assert_eq!(
+            board.legal_moves_from(Square::E1),
+            vec![
+                Square::C1,
+                Square::D1,
+                Square::F1,
+                Square::G1,
+                Square::D2,
+                Square::E2,
+                Square::F2
+            ]
+        );
+        assert_eq!(board.legal_moves_from(Square::H1).len(), 9);
+        assert!(board.legal_moves_from(Square::A8).is_empty());
+        assert!(board.legal_moves_from(Square::E4).is_empty());
+        assert!(board.legal_moves_from(Square::UNKNOWN).is_empty());
+    }
+
+    #[test]
+    fn test_legal_moves_map() {
+        let moves = Chessboard::new().legal_moves_map();
+
+        assert_eq!(moves.len(), 10);
+        assert_eq!(moves.values().map(Vec::len).sum::<usize>(), 20);
+        assert_eq!(moves[&Square::E2], vec![Square::E3, Square::E4]);
+        assert!(!moves.contains_key(&Square::E1));
+
+        let moves = Chessboard::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").legal_moves_map();
+        assert!(moves[&Square::E1].contains(&Square::G1));
+        assert!(moves[&Square::E1].contains(&Square::C1));
+        assert!(!moves.contains_key(&Square::E8));
+    }
+
+    #[test]
+    fn test_movetext() {
+        let mut board = Chessboard::new();
+        assert_eq!(board.movetext(), "");
+
+        for san in ["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7"] {
+            board.move_to(san).unwrap();
+        }
+        assert_eq!(board.movetext(), "1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7#");
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 12");
+        board.move_to("Kd7").unwrap();
+        board.move_to("e4").unwrap();
+        board.set_clock(1, std::time::Duration::from_secs(60));
+        assert_eq!(board.movetext(), "12... Kd7 13. e4");
+    }
+
+    #[test]
+    fn test_play_and_check() {
+        let mut board = Chessboard::new();
+
+        assert_eq!(board.play_and_check("f3"), Ok(None));
+        assert_eq!(board.play_and_check("e5"), Ok(None));
+        assert_eq!(board.play_and_check("g4"), Ok(None));
+        assert_eq!(
+            board.play_and_check("Qh4"),
+            Ok(Some(Termination::Checkmate))
+        );
+        assert_eq!(board.play_and_check("e4"), Err(MoveError::IllegalMove));
+    }
+
+    #[test]
+    fn test_castling_through_check() {
+        let castles = |fen: &str, king: Square| {
+            let moves = Chessboard::from_fen(fen).legal_moves_map();
+            let targets = moves.get(&king).cloned().unwrap_or_default();
+            (
+                targets
+                    .iter()
+                    .any(|&square| square == Square::G1 || square == Square::G8),
+                targets
+                    .iter()
+                    .any(|&square| square == Square::C1 || square == Square::C8),
+            )
+        };
+
+        // A rook on d8 covers d1, so only kingside castling is left even though c1 is safe.
+        assert_eq!(
+            castles("3rk3/8/8/8/8/8/8/R3K2R w KQ - 0 1", Square::E1),
+            (true, false)
+        );
+        // An attacked b1 doesn't matter, but an occupied one does.
+        assert_eq!(
+            castles("1r2k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", Square::E1),
+            (true, true)
+        );
+        assert_eq!(
+            castles("4k3/8/8/8/8/8/8/RN2K2R w KQ - 0 1", Square::E1),
+            (true, false)
+        );
+        // No castling out of check.
+        assert_eq!(
+            castles("4r1k1/8/8/8/8/8/8/R3K2R w KQ - 0 1", Square::E1),
+            (false, false)
+        );
+
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/4KR2 b kq - 0 1", Square::E8),
+            (false, true)
+        );
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/3RK3 b kq - 0 1", Square::E8),
+            (true, false)
+        );
+        assert_eq!(
+            castles("rn2k2r/8/8/8/8/8/8/4K3 b kq - 0 1", Square::E8),
+            (true, false)
+        );
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/4RK2 b kq - 0 1", Square::E8),
+            (false, false)
+        );
+    }
+
+    #[test]
+    fn test_status() {
+        let mut board = Chessboard::new();
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        assert!(!board.is_game_over());
+
+        for san in ["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7"] {
+            board.move_to(san).unwrap();
+        }
+        assert_eq!(board.status(), GameStatus::Checkmate { winner: true });
+        assert!(board.is_game_over());
+        assert!(!board.is_draw());
+
+        let draws = [
+            ("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", GameStatus::Stalemate),
+            (
+                "4k3/8/8/8/8/8/8/4KB2 w - - 0 1",
+                GameStatus::InsufficientMaterial,
+            ),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 100 80", GameStatus::FiftyMove),
+        ];
+        for (fen, status) in draws {
+            let board = Chessboard::from_fen(fen);
+            assert_eq!(board.status(), status);
+            assert!(board.is_draw());
+            assert!(board.is_game_over());
+        }
+
+        let mut board = Chessboard::new();
+        for _ in 0..2 {
+            for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+                board.move_to(san).unwrap();
+            }
+        }
+        assert_eq!(board.status(), GameStatus::ThreefoldRepetition);
+        assert!(board.is_draw());
+    }
+
+    #[test]
+    fn test_threefold_castling_rights() {
+        let mut board = Chessboard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+        for _ in 0..2 {
+            for san in ["Kf1", "Kf8", "Ke1", "Ke8"] {
+                board.move_to(san).unwrap();
+            }
+        }
+        assert!(!board.is_threefold_repetition());
+
+        for san in ["Kf1", "Kf8", "Ke1", "Ke8"] {
+            board.move_to(san).unwrap();
+        }
+        assert!(board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_zobrist_hash() {
+        let mut board = Chessboard::new();
+        let start = board.zobrist_hash();
+
+        board.move_to("e4").unwrap();
+        board.move_to("e5").unwrap();
+        board.move_to("Nf3").unwrap();
+        let after = board.zobrist_hash();
+        assert_ne!(after, start);
+
+        let mut transposed = Chessboard::new();
+        transposed.move_to("Nf3").unwrap();
+        transposed.move_to("e5").unwrap();
+        transposed.move_to("e4").unwrap();
+        assert_eq!(transposed.zobrist_hash(), after);
+
+        board.undo_move();
+        board.undo_move();
+        board.undo_move();
+        assert_eq!(board.zobrist_hash(), start);
+
+        assert_ne!(
+            Chessboard::from_fen("r3k3/8/8/8/8/8/8/4K3 w q - 0 1").zobrist_hash(),
+            Chessboard::from_fen("r3k3/8/8/8/8/8/8/4K3 w - - 0 1").zobrist_hash()
+        );
+        assert_ne!(
+            Chessboard::from_fen("r3k3/8/8/8/8/8/8/4K3 w - - 0 1").zobrist_hash(),
+            Chessboard::from_fen("r3k3/8/8/8/8/8/8/4K3 b - - 0 1").zobrist_hash()
+        );
+    }
+
+    #[test]
+    fn test_zobrist_hash_incremental() {
+        let mut board = Chessboard::new();
+
+        for san in [
+            "e4", "d5", "e5", "f5", "exf6", "Nc6", "fxg7", "Bf5", "gxh8=Q", "Qd7", "Nf3", "O-O-O",
+            "Be2", "e5", "O-O", "Kb8", "d4", "e4", "Nc3", "exf3", "Rb1",
+        ] {
+            board.move_to(san).unwrap();
+            assert_eq!(board.zobrist_hash(), board.zobrist_key(), "{san}");
+        }
+
+        while board.undo_move().is_some() {
+            assert_eq!(board.zobrist_hash(), board.zobrist_key());
+        }
+    }
+
+    #[test]
+    fn test_static_attack_masks_incremental() {
+        let games: [&[&str]; 3] = [
+            &[
+                "e4", "d5", "e5", "f5", "exf6", "Nc6", "fxg7", "Bf5", "gxh8=Q", "Qd7", "Nf3",
+                "O-O-O", "Be2", "e5", "O-O", "Kb8", "d4", "e4", "Nc3", "exf3", "Bxf3",
+            ],
+            &[
+                "e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6", "Ng5", "d5", "exd5", "Nxd5", "Nxf7",
+                "Kxf7", "Qf3+", "Ke6", "Nc3", "Ncb4",
+            ],
+            &[
+                "h4", "a5", "h5", "g5", "hxg6", "a4", "b4", "axb3", "gxh7", "bxa2", "hxg8=N",
+                "axb1=Q", "Rxb1",
+            ],
+        ];
+
+        for sans in games {
+            let mut board = Chessboard::new();
+
+            for san in sans {
+                board.move_to(san).unwrap();
+                assert_eq!(
+                    board.static_white_attack_mask,
+                    board.generate_static_attack_mask(true),
+                    "{san}"
+                );
+                assert_eq!(
+                    board.static_black_attack_mask,
+                    board.generate_static_attack_mask(false),
+                    "{san}"
+                );
+            }
+
+            while board.undo_move().is_some() {
+                assert_eq!(
+                    board.static_white_attack_mask,
+                    board.generate_static_attack_mask(true)
+                );
+                assert_eq!(
+                    board.static_black_attack_mask,
+                    board.generate_static_attack_mask(false)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_repetition_key_en_passant() {
+        let pinned = Chessboard::from_fen("8/8/8/8/k2pP2R/8/8/4K3 b - e3 0 1");
+        let without = Chessboard::from_fen("8/8/8/8/k2pP2R/8/8/4K3 b - - 0 1");
+        assert_eq!(pinned.zobrist_key(), without.zobrist_key());
+
+        let legal = Chessboard::from_fen("8/8/8/8/3pP2R/k7/8/4K3 b - e3 0 1");
+        let without = Chessboard::from_fen("8/8/8/8/3pP2R/k7/8/4K3 b - - 0 1");
+        assert_ne!(legal.zobrist_key(), without.zobrist_key());
+    }
+
+    #[test]
+    fn test_threefold_after_irreversible_move() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1".to_string();
+
+        let board = Chessboard::with_repetition_seed(&fen, &[fen.clone(), fen.clone()]);
+        assert!(!board.is_threefold_repetition());
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+        for san in ["Kd1", "Kd8", "Ke1", "Ke8", "e3", "Kd8", "Kd1", "Ke8", "Ke1"] {
+            board.move_to(san).unwrap();
+        }
+        assert!(!board.is_threefold_repetition());
+
+        for san in ["Kd8", "Kd1", "Ke8", "Ke1"] {
+            board.move_to(san).unwrap();
+        }
+        assert!(board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_with_repetition_seed() {
+        let after_nf3 = "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1".to_string();
+
+        let mut board = Chessboard::with_repetition_seed(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 4 3",
+            &[after_nf3.clone(), after_nf3.clone()],
+        );
+        assert!(!board.is_threefold_repetition());
+
+        board.move_to("Nf3").unwrap();
+        assert!(board.is_threefold_repetition());
+
+        let mut board = Chessboard::new();
+        board.move_to("Nf3").unwrap();
+        assert!(!board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_replay_pgn() {
+        let mut sans = Vec::new();
+        let mut fens = Vec::new();
+        let board = Chessboard::replay_pgn(
+            "1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O Be7 *",
+            |board, chess_move| {
+                sans.push(chess_move.san().to_string());
+                fens.push(board.get_fen());
+            },
+        )
+        .unwrap();
+
+        assert_eq!(sans.len(), 10);
+        assert_eq!(sans[8], "O-O");
+        assert_eq!(fens.last().unwrap(), &board.get_fen());
+        assert_eq!(
+            fens[0],
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+        );
+
+        assert_eq!(
+            Chessboard::replay_pgn("1. e4 e5 2. Ke3 *", |_, _| {}).unwrap_err(),
+            GameError::IllegalMove(2)
+        );
+    }
+
+    #[test]
+    fn test_threefold_undo() {
+        let mut board = Chessboard::new();
+
+        for _ in 0..2 {
+            board.move_to("Nf3").unwrap();
+            board.move_to("Nf6").unwrap();
+            board.move_to("Ng1").unwrap();
+            board.move_to("Ng8").unwrap();
+        }
+
+        assert!(board.is_threefold_repetition());
+
+        let last_move = board.undo_move().unwrap();
+        assert_eq!(
+            last_move.before(),
+            "rnbqkb1r/pppppppp/5n2/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 7 4"
+        );
+        assert!(!board.is_threefold_repetition());
+
+        board.move_to("Ng8").unwrap();
+        assert!(board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_undo_restores_state() {
+        let mut board = Chessboard::new();
+        let mut fens = vec![board.get_fen()];
+
+        for san in ["e4", "d5", "e5", "f5", "Ke2", "Nc6"] {
+            board.move_to(san).unwrap();
+            fens.push(board.get_fen());
+        }
+        assert_eq!(
+            fens[4],
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3"
+        );
+
+        while let Some(undone) = board.undo_move() {
+            fens.pop();
+            assert_eq!(board.get_fen(), *fens.last().unwrap());
+            assert_eq!(undone.before(), board.get_fen());
+        }
+
+        assert!(board.history.is_empty());
+        assert_eq!(board.get_fen(), START_FEN);
+        assert_eq!(board.legal_moves().len(), 20);
+    }
+
+    #[test]
+    fn test_threefold_current_position() {
+        let mut board = Chessboard::new();
+
+        for _ in 0..2 {
+            board.move_to("Nf3").unwrap();
+            board.move_to("Nf6").unwrap();
+            board.move_to("Ng1").unwrap();
+            board.move_to("Ng8").unwrap();
+        }
+        board.move_to("e4").unwrap();
+
+        assert!(!board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_promotion() {
+        let fen = "2b3k1/3PR3/8/8/8/8/8/6K1 w - - 0 1";
+        let mut board = Chessboard::from_fen(fen);
+
+        println!("{}", board.ascii());
+
+        board.move_to("dxc8=Q#").unwrap();
+
+        println!("{}", board.ascii());
+
+        assert_eq!(board.get_fen(), "2Q3k1/4R3/8/8/8/8/8/6K1 b - - 0 1");
+
+        assert!(board.is_mate());
+    }
+
+    #[test]
+    fn test_bench_movegen() {
+        let mut board = Chessboard::new();
+        let fen = board.get_fen();
+
+        let elapsed = board.bench_movegen(100);
+
+        assert!(elapsed > std::time::Duration::ZERO);
+        assert_eq!(board.get_fen(), fen);
+        assert_eq!(board.legal_moves().len(), 20);
+    }
+
+    #[test]
+    fn test_attacker_count() {
+        let board = Chessboard::from_fen("4k3/8/8/8/3r4/5n2/8/3QK3 w - - 0 1");
+        assert_eq!(board.attacker_count(Square::D2, Color::Black), 2);
+        assert_eq!(board.attacker_count(Square::D2, Color::White), 2);
+        assert_eq!(board.attacker_count(Square::A8, Color::Black), 0);
+    }
+
+    #[test]
+    fn test_is_defended() {
+        let board = Chessboard::from_fen("4k3/8/4p3/3n4/8/2N5/8/4K3 w - - 0 1");
+
+        assert!(!board.is_defended(Square::C3));
+        assert!(board.is_defended(Square::D5));
+        assert!(!board.is_defended(Square::E6));
+
+        let board = Chessboard::from_fen("4k3/8/8/3n4/8/2N5/8/3RK3 w - - 0 1");
+
+        assert!(!board.is_defended(Square::D5));
+        assert!(board.is_defended(Square::D1));
+        assert!(!board.is_defended(Square::C3));
+    }
+
+    #[test]
+    fn test_from_position() {
+        let board =
+            Chessboard::from_fen("r3k2r/pp1n1ppp/2p5/3pP3/8/2N2N2/PPP2PPP/R3K2R w KQq d6 0 12");
+
+        let position = Position {
+            board: board.to_array(),
+            turn: board.turn(),
+            castling: board.castling_rights(),
+            en_passant: board.en_passant(),
+            halfmove: board.halfmove_clock(),
+            fullmove: board.fullmove_number(),
+        };
+
+        let rebuilt = Chessboard::from_position(&position);
+        assert_eq!(rebuilt.get_fen(), board.get_fen());
+        assert_eq!(rebuilt.legal_moves(), board.legal_moves());
+        assert_eq!(rebuilt.to_array(), board.to_array());
+    }
+
+    #[test]
+    fn test_auto_queen() {
+        let mut board = Chessboard::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            board.preview("e8").unwrap().get_fen(),
+            "k3Q3/8/8/8/8/8/8/4K3 b - - 0 1"
+        );
+
+        board.set_auto_queen(false);
+        assert_eq!(board.play("e8"), Err(MoveError::PromotionRequired));
+        assert_eq!(
+            board.move_to("e8").unwrap_err(),
+            MoveError::PromotionRequired
+        );
+        assert!(board.history.is_empty());
+
+        assert_eq!(board.play("e8=R").unwrap(), "e8=R+");
+        board.undo_move();
+        assert_eq!(board.play("e8"), Err(MoveError::PromotionRequired));
+    }
+
+    #[test]
+    fn test_promotion_available() {
+        let board = Chessboard::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(board.has_promotion_available());
+
+        let promotions: Vec<Option<Piece>> = board
+            .legal_move_list()
+            .iter()
+            .filter(|info| info.from == Square::B7)
+            .map(|info| info.promotion)
+            .collect();
+        assert_eq!(
+            promotions,
+            vec![
+                Some(Piece::BISHOP),
+                Some(Piece::KNIGHT),
+                Some(Piece::ROOK),
+                Some(Piece::QUEEN)
+            ]
+        );
+
+        let board = Chessboard::from_fen("1n2k3/1P6/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(!board.has_promotion_available());
+
+        let board = Chessboard::new();
+        assert!(!board.has_promotion_available());
+    }
+
+    #[test]
+    fn test_capture_and_quiet_moves() {
+        let board = Chessboard::from_fen("3rk3/2P5/8/3pP3/8/8/8/R3K3 w Q d6 0 1");
+        let captures = board.capture_moves();
+        let quiet = board.quiet_moves();
+
+        assert_eq!(captures.len() + quiet.len(), board.legal_move_count());
+        assert_eq!(
+            captures
+                .iter()
+                .filter(|info| info.from == Square::C7 && info.to == Square::D8)
+                .count(),
+            4
+        );
+        assert!(captures.iter().any(|info| info.en_passant));
+        assert!(quiet.iter().all(|info| !info.en_passant));
+        assert!(quiet.iter().any(|info| info.castling.is_some()));
+        assert_eq!(
+            quiet
+                .iter()
+                .filter(|info| info.from == Square::C7 && info.to == Square::C8)
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_legal_moves_to() {
+        let board = Chessboard::from_fen("4k3/8/8/8/1b6/8/8/R3KN2 w - - 0 1");
+        let check_info = board.check_info();
+        let moves = board.legal_moves_to(check_info.checkers | check_info.block_squares);
+
+        let moves: Vec<(Square, Square)> = moves.iter().map(|info| (info.from, info.to)).collect();
+        assert_eq!(moves, vec![(Square::F1, Square::D2)]);
+
+        let board = Chessboard::new();
+        assert!(board
+            .legal_moves_to(Square::E4.into())
+            .iter()
+            .all(|info| info.to == Square::E4));
+        assert!(board.legal_moves_to(0).is_empty());
+    }
+
+    #[test]
+    fn test_non_drawing_moves() {
+        let mut board = Chessboard::new();
+        for san in ["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1"] {
+            board.move_to(san).unwrap();
+        }
+
+        let moves = board.non_drawing_moves();
+        assert_eq!(moves.len(), board.legal_move_count() - 1);
+        assert!(!moves
+            .iter()
+            .any(|info| info.from == Square::F6 && info.to == Square::G8));
+    }
+
+    #[test]
+    fn test_moves_for_color() {
+        let board = Chessboard::from_fen("r3k3/8/8/8/8/8/8/R3K2R b KQq - 0 1");
+        let white = board.moves_for_color(Color::White);
+
+        assert!(white
+            .iter()
+            .any(|info| info.castling == Some(CastlingType::KingSide)));
+        assert!(white
+            .iter()
+            .any(|info| info.castling == Some(CastlingType::QueenSide)));
+        assert!(white
+            .iter()
+            .all(|info| info.piece != Piece::UNKNOWN
+                && board.white_pieces() & u64::from(info.from) != 0));
+        assert_eq!(board.turn(), Color::Black);
+        assert_eq!(board.moves_for_color(Color::Black), board.legal_move_list());
+
+        let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/r3K3 b - - 0 1");
+        assert!(board
+            .moves_for_color(Color::White)
+            .iter()
+            .all(|info| info.piece == Piece::KING && info.to != Square::D1));
+    }
+
+    #[test]
+    fn test_legal_move_list_castling() {
+        let board = Chessboard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let castling: Vec<Option<CastlingType>> = board
+            .legal_move_list()
+            .iter()
+            .filter(|info| info.castling.is_some())
+            .map(|info| info.castling)
+            .collect();
+
+        assert_eq!(
+            castling,
+            vec![Some(CastlingType::QueenSide), Some(CastlingType::KingSide)]
+        );
+    }
+
+    #[test]
+    fn test_mobility() {
+        let board =
+            Chessboard::from_fen("rnbqkbnr/pppppppp/8/8/2B1P3/2N2N2/PPPP1PPP/R1BQK2R b KQkq - 0 4");
+
+        assert_eq!(board.mobility(Color::Black), 4);
+        assert!(board.mobility(Color::White) > board.mobility(Color::Black));
+    }
+
+    #[test]
+    fn test_lone_kings() {
+        let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+
+        assert_eq!(board.king_square(Color::White), Square::E1);
+        assert_eq!(board.king_square(Color::Black), Square::E8);
+        assert!(!board.is_checked());
+        assert!(!board.is_mate());
+        assert!(!board.is_stalemate());
+        assert!(board.has_moves());
+        assert!(board.is_insufficient_material());
+        assert_eq!(board.legal_moves().len(), 5);
+
+        let board = Chessboard::from_fen("7k/8/8/8/8/8/8/K7 w - - 0 1");
+
+        assert!(!board.is_stalemate());
+        assert_eq!(board.legal_moves(), vec!["Kb1", "Ka2", "Kb2"]);
+
+        let board = Chessboard::from_fen("k7/8/1K6/8/8/8/8/8 b - - 0 1");
+
+        assert!(!board.is_checked());
+        assert!(!board.is_stalemate());
+        assert_eq!(board.legal_moves(), vec!["Kb8"]);
+    }
+
+    #[test]
+    fn test_result() {
+        let board = Chessboard::from_fen("4R1k1/5ppp/8/8/8/8/8/6K1 b - - 0 1");
+        assert!(board.is_mate());
+        assert_eq!(board.result(), Some(GameResult::WhiteWins));
+
+        let board = Chessboard::from_fen("6k1/8/8/8/8/8/5PPP/3r2K1 w - - 0 1");
+        assert!(board.is_mate());
+        assert_eq!(board.result(), Some(GameResult::BlackWins));
+
+        let board = Chessboard::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!(board.result(), Some(GameResult::Draw));
+
+        let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(board.result(), Some(GameResult::Draw));
+
+        assert_eq!(Chessboard::new().result(), None);
+    }
+
+    #[test]
+    fn test_king_safe_squares() {
+        let board = Chessboard::from_fen("8/8/4k3/8/4K3/8/8/8 w - - 0 1");
+        let expected = [Square::D3, Square::E3, Square::F3, Square::D4, Square::F4]
+            .iter()
+            .fold(0, |mask, &square| mask | u64::from(square));
+        assert_eq!(board.king_safe_squares(Color::White), expected);
+
+        let board = Chessboard::from_fen("8/8/8/8/8/8/3r4/4K2k w - - 0 1");
+        assert_eq!(
+            board.king_safe_squares(Color::White),
+            u64::from(Square::D2) | u64::from(Square::F1)
+        );
+
+        let board = Chessboard::from_fen("8/8/8/8/8/2b5/3r4/4K2k w - - 0 1");
+        assert_eq!(board.king_safe_squares(Color::White), u64::from(Square::F1));
+    }
+
+    #[test]
+    fn test_mate_on_fiftieth_move() {
+        let mut board = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 99 80");
+        board.move_to("Ra8").unwrap();
+
+        assert!(board.is_fifty_moves());
+        assert_eq!(board.termination(), Some(Termination::Checkmate));
+        assert_eq!(board.result(), Some(GameResult::WhiteWins));
+
+        let mut board = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 99 80");
+        board.move_to("Rb1").unwrap();
+
+        assert_eq!(board.termination(), Some(Termination::FiftyMoves));
+        assert_eq!(board.result(), Some(GameResult::Draw));
+    }
+
+    #[test]
+    fn test_position_legal() {
+        assert!(Chessboard::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").is_position_legal());
+        assert!(!Chessboard::from_fen("4k3/8/8/8/8/8/8/4K2r b - - 0 1").is_position_legal());
+        assert!(!Chessboard::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1").is_position_legal());
+        assert!(!Chessboard::from_fen("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").is_position_legal());
+        assert!(!Chessboard::from_fen("4k3/8/8/8/8/8/8/P3K3 w - - 0 1").is_position_legal());
+        assert!(!Chessboard::from_fen("8/8/8/8/8/8/3k4/4K3 w - - 0 1").is_position_legal());
+    }
+
+    #[test]
+    fn test_try_from_fen_kings() {
+        assert_eq!(
+            Chessboard::try_from_fen("8/8/8/8/8/8/8/8 w - - 0 1").unwrap_err(),
+            FenError::InvalidKings
+        );
+        assert_eq!(
+            Chessboard::try_from_fen("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").unwrap_err(),
+            FenError::InvalidKings
+        );
+
+        let board = Chessboard::from_fen("8/8/8/8/8/8/8/8 w - - 0 1");
+        assert!(!board.is_position_legal());
+        assert_eq!(board.king_square(Color::White), Square::UNKNOWN);
+    }
+
+    #[test]
+    fn test_pawn_endgame() {
+        let board = Chessboard::from_fen("8/5k2/3p4/3P4/8/8/2K5/8 w - - 0 1");
+        assert!(board.is_pawn_endgame());
 
-            board.push('\n');
+        let board = Chessboard::from_fen("8/5k2/3p4/3P4/8/8/2K2N2/8 w - - 0 1");
+        assert!(!board.is_pawn_endgame());
+
+        let board = Chessboard::from_fen("8/5k2/3p1b2/3P4/8/8/2K5/8 w - - 0 1");
+        assert!(!board.is_pawn_endgame());
+
+        assert!(!Chessboard::new().is_pawn_endgame());
+    }
+
+    #[test]
+    fn test_kpk_result() {
+        let won = [
+            "4k3/8/4K3/4P3/8/8/8/8 w - - 0 1",
+            "4k3/8/4K3/4P3/8/8/8/8 b - - 0 1",
+            "8/4k3/8/4K3/4P3/8/8/8 b - - 0 1",
+            "8/8/8/2P5/5k2/8/8/7K w - - 0 1",
+        ];
+        for fen in won {
+            assert_eq!(
+                Chessboard::from_fen(fen).kpk_result(),
+                Some(GameResult::WhiteWins),
+                "{}",
+                fen
+            );
         }
-        board
+
+        let drawn = [
+            "8/4k3/8/4K3/4P3/8/8/8 w - - 0 1",
+            "8/8/8/2P5/5k2/8/8/7K b - - 0 1",
+            "7k/8/8/8/8/8/7P/7K w - - 0 1",
+            "4k3/4P3/4K3/8/8/8/8/8 b - - 0 1",
+        ];
+        for fen in drawn {
+            assert_eq!(
+                Chessboard::from_fen(fen).kpk_result(),
+                Some(GameResult::Draw),
+                "{}",
+                fen
+            );
+        }
+
+        let board = Chessboard::from_fen("7k/8/8/5K2/2p5/8/8/8 b - - 0 1");
+        assert_eq!(board.kpk_result(), Some(GameResult::BlackWins));
+
+        assert_eq!(Chessboard::new().kpk_result(), None);
+        assert_eq!(
+            Chessboard::from_fen("4k3/8/8/8/8/8/4PP2/4K3 w - - 0 1").kpk_result(),
+            None
+        );
     }
 
-    /// Returns the legal moves of the current position.
-    /// # Examples
-    /// ```
-    /// use w_chess::Chessboard;
-    /// let board = Chessboard::new();
-    /// let legal_moves = board.legal_moves();
-    /// ```
-    pub fn legal_moves(&self) -> Vec<String> {
-        let mut legal_moves = Vec::new();
-        for (&square, &moves) in self.legal_moves.iter() {
-            let piece = self.get_piece(square);
-
-            let prefix = match piece {
-                Piece::KING => Some('K'),
-                Piece::QUEEN => Some('Q'),
-                Piece::ROOK => Some('R'),
-                Piece::BISHOP => Some('B'),
-                Piece::KNIGHT => Some('N'),
-                _ => None,
-            };
+    #[test]
+    fn test_insufficient_material() {
+        assert!(Chessboard::from_fen("4k3/8/8/8/8/8/8/4KN2 w - - 0 1").is_insufficient_material());
+        assert!(Chessboard::from_fen("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1").is_insufficient_material());
+        assert!(
+            !Chessboard::from_fen("4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1").is_insufficient_material()
+        );
+        assert!(!Chessboard::from_fen("4k3/8/8/8/8/8/8/3NKN2 w - - 0 1").is_insufficient_material());
+        assert!(!Chessboard::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").is_insufficient_material());
+        assert!(!Chessboard::new().is_insufficient_material());
+    }
 
-            for square in Chessboard::get_squares(moves) {
-                match piece {
-                    Piece::KING => {
-                        if (self.turn
-                            && self.castle_rights[0]
-                            && square & WHITE_KING_SIDE_CASTLE_SQUARE != 0)
-                            || (!self.turn
-                                && self.castle_rights[2]
-                                && square & BLACK_KING_SIDE_CASTLE_SQUARE != 0)
-                        {
-                            legal_moves.push("O-O".to_string());
-                            continue;
-                        } else if (self.turn
-                            && self.castle_rights[1]
-                            && square & WHITE_QUEEN_SIDE_CASTLE_SQUARE != 0)
-                            || (!self.turn
-                                && self.castle_rights[3]
-                                && square & BLACK_QUEEN_SIDE_CASTLE_SQUARE != 0)
-                        {
-                            legal_moves.push("O-O-O".to_string());
-                            continue;
-                        }
-                    }
-                    _ => {}
-                }
-                match prefix {
-                    Some(p) => legal_moves.push(format!("{}{}", p, Square::from(square))),
-                    None => legal_moves.push(Square::from(square).to_string()),
-                }
-            }
+    #[test]
+    fn test_successor_positions() {
+        let board = Chessboard::new();
+        let successors = board.successor_positions();
+
+        assert_eq!(successors.len(), 20);
+        for (_, fen) in successors.iter() {
+            assert!(Chessboard::from_fen_checked(fen).is_ok());
         }
-        legal_moves
+        assert!(successors.contains(&(
+            "Nf3".to_string(),
+            "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1".to_string()
+        )));
+
+        let board = Chessboard::from_fen("4k3/8/8/8/8/8/5n2/R4RK1 w - - 0 1");
+        let sans: Vec<String> = board
+            .successor_positions()
+            .into_iter()
+            .map(|(san, _)| san)
+            .collect();
+
+        assert!(sans.contains(&"Rad1".to_string()));
+        assert!(sans.contains(&"Rfd1".to_string()));
+        assert!(sans.contains(&"Ra8+".to_string()));
+        assert!(sans.contains(&"Rxf2".to_string()));
+        assert!(sans.contains(&"Kxf2".to_string()));
+        assert!(!sans.contains(&"Rd1".to_string()));
     }
-}
 
-impl std::fmt::Display for Chessboard {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.ascii())
+    #[test]
+    fn test_full_move_number() {
+        let mut board = Chessboard::new();
+
+        board.move_to("e4").unwrap();
+        assert_eq!(board.fullmove_number(), 1);
+        board.move_to("e5").unwrap();
+        assert_eq!(board.fullmove_number(), 2);
+        board.move_to("Nf3").unwrap();
+        assert_eq!(board.fullmove_number(), 2);
+
+        let mut board = Chessboard::from_fen("r3k3/6P1/8/8/8/8/1p6/4K2R b Kq - 0 30");
+
+        board.move_to("b1=Q+").unwrap();
+        assert_eq!(board.fullmove_number(), 31);
+        board.move_to("Ke2").unwrap();
+        board.move_to("O-O-O").unwrap();
+        assert_eq!(board.fullmove_number(), 32);
+        board.move_to("g8=Q").unwrap();
+        assert_eq!(board.fullmove_number(), 32);
+        assert_eq!(board.get_fen(), "2kr2Q1/8/8/8/8/8/4K3/1q5R b - - 0 32");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_occupancy() {
+        let board = Chessboard::new();
+        assert_eq!(board.white_pieces().count_ones(), 16);
+        assert_eq!(board.black_pieces().count_ones(), 16);
+        assert_eq!(board.occupied(), RANK_1 | RANK_2 | RANK_7 | RANK_8);
+        assert_eq!(board.piece_bitboard(Piece::PAWN), RANK_2 | RANK_7);
+        assert_eq!(board.piece_bitboard(Piece::QUEEN).count_ones(), 2);
+        assert_eq!(board.piece_bitboard(Piece::UNKNOWN), 0);
+    }
 
     #[test]
-    fn test_generate_moves() {
-        let board = Chessboard::from_fen("8/4PnK1/4P3/2p3p1/1p2BPk1/P7/2pR2PB/5n2 w - - 0 1");
+    fn test_castling_rook_move() {
+        let board = Chessboard::new();
+        assert_eq!(
+            board.castling_rook_move(CastlingType::KingSide, Color::White),
+            Some((Square::H1, Square::F1))
+        );
+        assert_eq!(
+            board.castling_rook_move(CastlingType::QueenSide, Color::Black),
+            Some((Square::A8, Square::D8))
+        );
 
-        for (&square, &valid_move) in board.legal_moves.iter() {
-            let board_square: u64 = square.into();
-            let color: bool = board.white & board_square != 0;
-            if color != board.turn {
-                continue;
-            }
-            println!(
-                "{} on {}",
-                board.get_piece(square.into()),
-                Square::from(square)
+        let board = Chessboard::from_fen("r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1");
+        assert_eq!(
+            board.castling_rook_move(CastlingType::QueenSide, Color::White),
+            None
+        );
+        assert_eq!(
+            board.castling_rook_move(CastlingType::KingSide, Color::Black),
+            None
+        );
+    }
+
+    #[test]
+    fn test_canonical_fen() {
+        let black = Chessboard::from_fen("r3k2r/8/8/8/3pP3/8/8/4K2R b Kkq e3 0 1");
+        let white = Chessboard::from_fen("4k2r/8/8/3Pp3/8/8/8/R3K2R w KQk e6 0 1");
+
+        assert_eq!(black.canonical_fen(), white.get_fen());
+        assert_eq!(white.canonical_fen(), white.get_fen());
+    }
+
+    #[test]
+    fn test_castling_display() {
+        for fen in [
+            START_FEN,
+            "r3k2r/8/8/8/8/8/8/R3K2R w Qk - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R b q - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        ] {
+            let board = Chessboard::from_fen(fen);
+            assert_eq!(
+                board.castling_display(),
+                board.get_fen().split_whitespace().nth(2).unwrap()
+            );
+            assert_eq!(
+                board.castling_display(),
+                fen.split_whitespace().nth(2).unwrap()
             );
-            for square in Chessboard::get_squares(valid_move) {
-                println!("valid_move: {}", Square::from(square));
-            }
         }
+
+        let mut board = Chessboard::new();
+        board.move_to("e4").unwrap();
+        board.move_to("e5").unwrap();
+        board.move_to("Ke2").unwrap();
+        assert_eq!(board.castling_display(), "kq");
     }
 
     #[test]
-    fn get_fen_works() {
-        let fen = "8/PK4N1/P1p2rn1/7p/1P1B3P/2P5/p1NR4/5k2 w - - 0 1";
-        let board = Chessboard::from_fen(fen);
+    fn test_preview() {
+        let board = Chessboard::new();
+        let preview = board.preview("e4").unwrap();
 
-        assert_eq!(board.get_fen(), fen);
+        assert_eq!(board.get_fen(), START_FEN);
+        assert!(board.history.is_empty());
+        assert_eq!(
+            preview.get_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+        );
+        assert_eq!(preview.history.len(), 1);
+
+        assert_eq!(board.preview("e5").unwrap_err(), MoveError::IllegalMove);
+        assert_eq!(
+            board.preview("e9").unwrap_err(),
+            MoveError::InvalidSan(SanParseError::InvalidRank)
+        );
     }
 
     #[test]
-    fn test_mate() {
-        let board =
-            Chessboard::from_fen("r1bqkbnr/pppp1Qpp/8/4p3/1nB1P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 4");
+    fn test_chess_move_squares() {
+        let mut board = Chessboard::new();
+        board.move_to("Nf3").unwrap();
+
+        let last_move = board.history.last().unwrap();
+        assert_eq!(last_move.from_square(), Square::G1);
+        assert_eq!(last_move.to_square(), Square::F3);
+        assert_eq!(last_move.piece(), Piece::KNIGHT);
 
-        assert_eq!(board.is_mate(), true);
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        board.move_to("O-O").unwrap();
+
+        let last_move = board.history.last().unwrap();
+        assert_eq!(last_move.from_square(), Square::E1);
+        assert_eq!(last_move.to_square(), Square::G1);
+        assert_eq!(last_move.piece(), Piece::KING);
     }
 
     #[test]
-    fn test_move_to() {
+    fn test_gives_check() {
+        let board = Chessboard::from_fen("4k3/8/8/8/4N3/8/8/4R1K1 w - - 0 1");
+        assert!(board.gives_check(Square::E4, Square::C3, None));
+        assert!(board.gives_check(Square::E4, Square::F6, None));
+        assert!(!board.gives_check(Square::G1, Square::G2, None));
+        assert!(!board.gives_check(Square::E4, Square::E5, None));
+
+        let board = Chessboard::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(board.gives_check(Square::E7, Square::E8, Some(Piece::QUEEN)));
+        assert!(!board.gives_check(Square::E7, Square::E8, Some(Piece::KNIGHT)));
+        assert!(!board.gives_check(Square::E7, Square::E8, None));
+    }
+
+    #[test]
+    fn test_find_mate_in_one() {
+        let board = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        let mates: Vec<(Square, Square)> = board
+            .find_mate_in_one()
+            .iter()
+            .map(|info| (info.from, info.to))
+            .collect();
+        assert_eq!(mates, vec![(Square::A1, Square::A8)]);
+
+        let board = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/RR4K1 w - - 0 1");
+        let mates: Vec<(Square, Square)> = board
+            .find_mate_in_one()
+            .iter()
+            .map(|info| (info.from, info.to))
+            .collect();
+        assert_eq!(
+            mates,
+            vec![(Square::A1, Square::A8), (Square::B1, Square::B8)]
+        );
+
+        assert!(Chessboard::new().find_mate_in_one().is_empty());
+    }
+
+    #[test]
+    fn test_moves_since() {
         let mut board = Chessboard::new();
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bb5"] {
+            board.move_to(san).unwrap();
+        }
 
-        board.move_to("f4");
-        board.move_to("f5");
-        board.move_to("Nf3");
-        board.move_to("Nc6");
-        board.move_to("e4");
-        board.move_to("e5");
-        board.move_to("Qe2");
-        board.move_to("Bb4");
-        board.move_to("d4");
-        board.move_to("c3");
-        println!("{}", board.get_fen());
+        assert_eq!(board.moves_since(2), vec!["Nf3", "Nc6", "Bb5"]);
+        assert_eq!(board.moves_since(0).len(), 5);
+        assert!(board.moves_since(5).is_empty());
+        assert!(board.moves_since(9).is_empty());
     }
 
     #[test]
-    fn test_random_board() {
-        let mut board =
-            Chessboard::from_fen("3B4/R2p1P2/3p2p1/5NP1/2K1n3/1Q6/1p3R1p/2n1k3 w - - 0 1");
+    fn test_same_move() {
+        let mut long = Chessboard::new();
+        long.move_to("Ng1f3").unwrap();
+        let mut short = Chessboard::new();
+        short.move_to("Nf3").unwrap();
+
+        let long = long.history.last().unwrap();
+        let short = short.history.last().unwrap();
+        assert_ne!(long, short);
+        assert!(long.same_move(short));
+
+        let mut other = Chessboard::new();
+        other.move_to("Nc3").unwrap();
+        assert!(!other.history.last().unwrap().same_move(short));
+    }
 
-        println!("{}", board.ascii());
+    #[test]
+    fn test_double_push() {
+        let mut board = Chessboard::new();
+        board.move_to("e4").unwrap();
+        assert!(board.history.last().unwrap().is_double_push());
 
-        board.move_to("Qe3");
-        board.move_to("Kd1");
-        board.move_to("Rf1");
-        board.move_to("Kc2");
-        board.move_to("Qe4");
-        board.move_to("Kd2");
-        board.move_to("Ba5");
+        board.move_to("e6").unwrap();
+        assert!(!board.history.last().unwrap().is_double_push());
 
-        println!("{}", board.get_fen());
-        println!("{}", board.ascii());
+        let mut board = Chessboard::new();
+        board.move_to("e3").unwrap();
+        assert!(!board.history.last().unwrap().is_double_push());
 
-        assert!(board.is_mate());
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1");
+        board.move_to("Ra3").unwrap();
+        assert!(!board.history.last().unwrap().is_double_push());
     }
 
     #[test]
-    fn castle_white_queen_side() {
-        let fen = "rnb1kbnr/pp2pppp/8/1q6/8/8/P3PPPP/R3K1NR w KQkq - 0 1";
-        let mut board = Chessboard::from_fen(fen);
+    fn test_previous_fen() {
+        let mut board = Chessboard::new();
+        board.move_to("e4").unwrap();
+        assert_eq!(board.previous_fen().unwrap(), START_FEN);
 
-        board.move_to("O-O-O"); // or Kc1
-        println!("{}", board);
+        board.move_to("e5").unwrap();
+        assert_eq!(
+            board.previous_fen().unwrap(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+        );
+        assert_eq!(board.history.len(), 2);
+    }
+
+    #[test]
+    fn test_undo_move_clears_redo() {
+        let mut board = Chessboard::new();
+        for san in ["e4", "e5", "Nf3"] {
+            board.move_to(san).unwrap();
+        }
 
+        assert!(board.step_back());
+        assert!(board.undo_move().is_some());
+        assert!(!board.step_forward());
+        assert_eq!(board.moves_since(0), vec!["e4"]);
         assert_eq!(
             board.get_fen(),
-            "rnb1kbnr/pp2pppp/8/1q6/8/8/P3PPPP/2KR2NR b kq - 1 1"
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
         );
     }
 
     #[test]
-    fn castle_white_king_side() {
-        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPP2P/RNBQK2R w KQkq - 0 1";
-        let mut board = Chessboard::from_fen(fen);
-
-        println!("{:?}", board.legal_moves());
+    fn test_step_back_and_forward() {
+        let mut board = Chessboard::new();
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6", "O-O"] {
+            board.move_to(san).unwrap();
+        }
 
-        board.move_to("O-O"); // or Kg1
+        assert!(board.step_back());
+        assert!(board.step_back());
+        assert!(board.step_forward());
+        assert_eq!(board.cursor(), 6);
+        assert_eq!(
+            board.get_fen(),
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4"
+        );
 
+        assert!(board.step_forward());
+        assert!(!board.step_forward());
+        assert_eq!(board.history.len(), 7);
         assert_eq!(
             board.get_fen(),
-            "rnbqkbnr/pppppppp/8/8/8/8/PPPPP2P/RNBQ1RK1 b kq - 1 1"
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4"
         );
+
+        while board.step_back() {}
+        assert_eq!(board.get_fen(), START_FEN);
+
+        board.move_to("d4").unwrap();
+        assert!(!board.step_forward());
+        assert_eq!(board.cursor(), 1);
     }
 
     #[test]
-    fn castle_black_queen_side() {
-        let fen = "r3kbnr/p3pppp/8/8/1Q6/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1";
-        let mut board = Chessboard::from_fen(fen);
+    fn test_en_passant_move_info() {
+        let mut board = Chessboard::from_fen("4k3/8/8/8/2p5/8/3P4/4K3 w - - 0 1");
+        board.move_to("d4").unwrap();
+
+        let en_passant: Vec<MoveInfo> = board
+            .legal_move_list()
+            .into_iter()
+            .filter(|info| info.en_passant)
+            .collect();
+
+        assert_eq!(en_passant.len(), 1);
+        assert_eq!(en_passant[0].from, Square::C4);
+        assert_eq!(en_passant[0].to, Square::D3);
+        assert_eq!(en_passant[0].captured, Some(Piece::PAWN));
+
+        board.move_to("cxd3").unwrap();
+        let last_move = board.history.last().unwrap();
+        assert!(last_move.is_en_passant());
+        assert!(!board.history[0].is_en_passant());
+    }
 
-        board.move_to("O-O-O"); // Kc8
+    #[test]
+    fn test_responses_after() {
+        let board = Chessboard::from_fen("7k/8/5K2/8/8/8/8/R7 w - - 0 1");
 
+        assert_eq!(board.responses_after("Ra8+").unwrap(), 1);
+        assert_eq!(board.responses_after("Ra7").unwrap(), 1);
+        assert_eq!(board.responses_after("Rb1").unwrap(), 2);
         assert_eq!(
-            board.get_fen(),
-            "2kr1bnr/p3pppp/8/8/1Q6/8/PPPPPPPP/RNBQKBNR w KQ - 1 2"
+            board.responses_after("Ra2").unwrap(),
+            board.preview("Ra2").unwrap().legal_move_count()
+        );
+        assert_eq!(
+            board.responses_after("Kh7").unwrap_err(),
+            MoveError::IllegalMove
         );
     }
 
     #[test]
-    fn castle_black_king_side() {
-        let fen = "rnbqk2r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1";
-        let mut board = Chessboard::from_fen(fen);
+    fn test_legal_move_list_order() {
+        let fen = "r3k2r/1P3ppp/2n5/3pP3/8/2N2N2/PPP2PPP/R3K2R w KQkq d6 0 12";
+        let first = Chessboard::from_fen(fen).legal_move_list();
+        let second = Chessboard::from_fen(fen).legal_move_list();
+
+        assert_eq!(first.len(), second.len());
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a, b);
+        }
+
+        for pair in first.windows(2) {
+            let a = (
+                pair[0].from as u8,
+                pair[0].to as u8,
+                pair[0].promotion.map(|p| p as u8),
+            );
+            let b = (
+                pair[1].from as u8,
+                pair[1].to as u8,
+                pair[1].promotion.map(|p| p as u8),
+            );
+            assert!(a < b);
+        }
+    }
+
+    #[test]
+    fn test_pawn_moves_on_edges() {
+        let board = Chessboard::from_fen("4k3/8/8/8/8/1p6/P6p/4K3 w - - 0 1");
+        let moves: Vec<Square> = board
+            .legal_move_list()
+            .iter()
+            .filter(|info| info.from == Square::A2)
+            .map(|info| info.to)
+            .collect();
+
+        assert_eq!(moves, vec![Square::A3, Square::B3, Square::A4]);
+
+        let board = Chessboard::from_fen("4k3/P6p/6N1/8/8/8/8/4K3 b - - 0 1");
+        let moves: Vec<Square> = board
+            .legal_move_list()
+            .iter()
+            .filter(|info| info.from == Square::H7)
+            .map(|info| info.to)
+            .collect();
+
+        assert_eq!(moves, vec![Square::H5, Square::G6, Square::H6]);
+
+        let board = Chessboard::from_fen("4k3/8/8/8/p7/8/7P/4K3 w - - 0 1");
+        let moves: Vec<Square> = board
+            .legal_move_list()
+            .iter()
+            .filter(|info| info.from == Square::H2)
+            .map(|info| info.to)
+            .collect();
+
+        assert_eq!(moves, vec![Square::H3, Square::H4]);
+    }
 
-        board.move_to("O-O"); // Kg8
+    #[test]
+    fn test_check_info() {
+        let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1");
+        let check_info = board.check_info();
 
+        assert_eq!(check_info.checkers, Square::A1.into());
         assert_eq!(
-            board.get_fen(),
-            "rnbq1rk1/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 1 2"
+            check_info.block_squares,
+            u64::from(Square::B1) | u64::from(Square::C1) | u64::from(Square::D1)
+        );
+
+        let board = Chessboard::from_fen("4k3/8/8/8/1b6/8/8/4K3 w - - 0 1");
+        let check_info = board.check_info();
+
+        assert_eq!(check_info.checkers, Square::B4.into());
+        assert_eq!(
+            check_info.block_squares,
+            u64::from(Square::C3) | u64::from(Square::D2)
+        );
+
+        let board = Chessboard::from_fen("4k3/8/8/8/8/3n4/8/4K3 w - - 0 1");
+        let check_info = board.check_info();
+
+        assert_eq!(check_info.checkers, Square::D3.into());
+        assert_eq!(check_info.block_squares, 0);
+
+        let board = Chessboard::from_fen("4k3/8/8/8/8/3n4/8/r3K3 w - - 0 1");
+        let check_info = board.check_info();
+
+        assert_eq!(
+            check_info.checkers,
+            u64::from(Square::D3) | u64::from(Square::A1)
+        );
+        assert_eq!(check_info.block_squares, 0);
+
+        let board = Chessboard::new();
+        assert_eq!(
+            board.check_info(),
+            CheckInfo {
+                checkers: 0,
+                block_squares: 0
+            }
         );
     }
 
     #[test]
-    fn test_en_passant() {
-        let fen = "rnbqkbnr/pppp1ppp/8/8/4p3/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    fn test_en_passant_accessor() {
+        let mut board = Chessboard::from_fen("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1");
+        assert_eq!(board.en_passant(), None);
 
-        let mut board = Chessboard::from_fen(fen);
+        board.move_to("e4").unwrap();
+        assert_eq!(board.en_passant(), Some(Square::E3));
+        assert_eq!(board.get_fen(), "4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1");
 
-        board.move_to("d4");
-        println!("{}", board.ascii());
-        board.move_to("d3");
-        println!("{}", board.ascii());
-        println!("{:?}", board.history);
+        board.move_to("Kd7").unwrap();
+        assert_eq!(board.en_passant(), None);
+
+        let board = Chessboard::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2");
+        assert_eq!(board.en_passant(), Some(Square::D6));
+    }
+
+    #[test]
+    fn test_three_knight_disambiguation() {
+        let fen = "4k3/8/8/1N3N2/8/1N6/8/4K3 w - - 0 1";
+
+        for (input, canonical) in [
+            ("Nb5d4", "Nb5d4"),
+            ("Nb3d4", "N3d4"),
+            ("Nf5d4", "Nfd4"),
+            ("Nb5d6", "Nbd6+"),
+            ("Nf5d6", "Nfd6+"),
+            ("Nb5c3", "Nc3"),
+        ] {
+            let mut board = Chessboard::from_fen(fen);
+            assert_eq!(board.play(input).unwrap(), canonical, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_double_push_en_passant() {
+        let mut board = Chessboard::from_fen("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1");
+        board.play("e4").unwrap();
+        assert_eq!(board.en_passant(), Some(Square::E3));
+
+        let mut board = Chessboard::from_fen("4k3/4p3/8/5P2/8/8/8/4K3 b - - 0 1");
+        board.play("e5").unwrap();
+        assert_eq!(board.en_passant(), Some(Square::E6));
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/3p4/4P3/8/4K3 w - - 0 1");
+        board.play("e4").unwrap();
+        assert_eq!(board.en_passant(), None);
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/4n3/4P3/4K3 w - - 0 1");
+        assert_eq!(board.play("e4").unwrap_err(), MoveError::IllegalMove);
+        assert!(!board
+            .legal_move_list()
+            .iter()
+            .any(|info| info.from == Square::E2 && info.to == Square::E4));
+        assert_eq!(board.en_passant(), None);
+    }
+
+    #[test]
+    fn test_play() {
+        let mut board = Chessboard::new();
+
+        assert_eq!(board.play("Ng1f3").unwrap(), "Nf3");
+        assert_eq!(board.play("Ke7").unwrap_err(), MoveError::IllegalMove);
+        assert_eq!(board.play("e5").unwrap(), "e5");
+        assert_eq!(board.play("Nxe5").unwrap(), "Nxe5");
+        assert_eq!(board.play("f6").unwrap(), "f6");
+        assert_eq!(board.play("Nf3").unwrap(), "Nf3");
+        assert_eq!(board.play("Kf7").unwrap(), "Kf7");
+        assert_eq!(board.play("Ng5").unwrap(), "Ng5+");
+        assert_eq!(board.history.last().unwrap().san(), "Ng5+");
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1");
+        assert_eq!(board.play("0-0").unwrap(), "O-O");
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1");
+        assert_eq!(board.play("Rad1").unwrap(), "Rad1");
+
+        let mut board = Chessboard::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1");
+        assert_eq!(board.play("Rf1d1").unwrap(), "Rfd1");
+    }
+
+    #[test]
+    fn test_en_passant_cleared() {
+        let mut board = Chessboard::from_fen("4k3/3p4/8/4P3/8/8/8/4K2R b K - 0 1");
+        board.move_to("d5").unwrap();
+        assert_eq!(board.en_passant(), Some(Square::D6));
+
+        board.move_to("O-O").unwrap();
+        assert_eq!(board.en_passant(), None);
+        assert_eq!(board.get_fen(), "4k3/8/8/3pP3/8/8/8/5RK1 b - - 1 2");
+
+        let mut board = Chessboard::from_fen("4k3/3p3P/8/4P3/8/8/8/4K3 b - - 0 1");
+        board.move_to("d5").unwrap();
+        assert_eq!(board.en_passant(), Some(Square::D6));
+
+        board.move_to("h8=Q+").unwrap();
+        assert_eq!(board.en_passant(), None);
+        assert_eq!(board.get_fen(), "4k2Q/8/8/3pP3/8/8/8/4K3 b - - 0 2");
+    }
+
+    #[test]
+    fn test_en_passant_cleared_by_promotion_capture() {
+        let mut board = Chessboard::from_fen("1n2k3/P2p4/8/4P3/8/8/8/4K3 b - - 0 1");
+        board.move_to("d5").unwrap();
+        assert_eq!(board.en_passant(), Some(Square::D6));
+
+        assert_eq!(board.play("axb8=Q").unwrap(), "axb8=Q+");
+        assert_eq!(board.en_passant(), None);
+        assert_eq!(board.get_fen(), "1Q2k3/8/8/3pP3/8/8/8/4K3 b - - 0 2");
+    }
 
+    #[test]
+    fn test_attacks_from() {
+        let board = Chessboard::from_fen("4k3/6p1/8/8/3B4/8/1P6/4K3 w - - 0 1");
+        let expected = [
+            Square::C3,
+            Square::B2,
+            Square::E3,
+            Square::F2,
+            Square::G1,
+            Square::C5,
+            Square::B6,
+            Square::A7,
+            Square::E5,
+            Square::F6,
+            Square::G7,
+        ]
+        .iter()
+        .fold(0, |mask, &square| mask | u64::from(square));
+
+        assert_eq!(board.attacks_from(Square::D4), expected);
         assert_eq!(
-            board.get_fen(),
-            "rnbqkbnr/pppp1ppp/8/8/8/3p4/PPP1PPPP/RNBQKBNR w KQkq - 0 2"
+            board.attacks_from(Square::B2),
+            u64::from(Square::A3) | u64::from(Square::C3)
+        );
+        assert_eq!(
+            board.attacks_from(Square::G7),
+            u64::from(Square::F6) | u64::from(Square::H6)
         );
+        assert_eq!(board.attacks_from(Square::D5), 0);
     }
 
     #[test]
-    fn test_threefold() {
-        let mut board = Chessboard::new();
+    fn test_blocked_position() {
+        let board = Chessboard::from_fen("4k3/8/1p1p1p1p/pPpPpPpP/P1P1P1P1/8/8/4K3 w - - 0 1");
+        assert!(board.is_blocked_position());
 
-        board.move_to("Nf3");
-        board.move_to("Nf6");
+        let board = Chessboard::from_fen("4k3/8/8/3p4/3P4/8/8/4K3 w - - 0 1");
+        assert!(board.is_blocked_position());
 
-        board.move_to("Ng1");
-        board.move_to("Ng8");
+        let board = Chessboard::from_fen("4k3/8/8/3pp3/3P4/8/8/4K3 w - - 0 1");
+        assert!(!board.is_blocked_position());
 
-        board.move_to("Nf3");
-        board.move_to("Nf6");
+        let board = Chessboard::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(!board.is_blocked_position());
+        assert!(!Chessboard::new().is_blocked_position());
+    }
 
-        board.move_to("Ng1");
-        board.move_to("Ng8");
+    #[test]
+    fn test_legalize_pinned_bishop() {
+        let board = Chessboard::from_fen("4k3/8/8/b7/8/8/3B4/4K3 w - - 0 1");
+        let pseudo_targets = board.attacks_from(Square::D2);
 
-        assert!(board.is_threefold_repetition());
         assert_eq!(
-            board.get_fen(),
-            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 8 5"
+            board.legalize(Square::D2, pseudo_targets),
+            u64::from(Square::C3) | u64::from(Square::B4) | u64::from(Square::A5)
         );
+        assert_eq!(board.legalize(Square::A5, u64::from(Square::B4)), 0);
     }
 
     #[test]
-    fn test_promotion() {
-        let fen = "2b3k1/3PR3/8/8/8/8/8/6K1 w - - 0 1";
+    fn test_attack_map_without() {
+        let board = Chessboard::from_fen("4k3/8/8/8/4B3/8/8/4R1K1 w - - 0 1");
+        let behind_bishop = u64::from(Square::E5)
+            | u64::from(Square::E6)
+            | u64::from(Square::E7)
+            | u64::from(Square::E8);
+
+        assert_eq!(board.attack_map_without(0, Color::White) & behind_bishop, 0);
+        assert_eq!(
+            board.attack_map_without(Square::E4.into(), Color::White) & behind_bishop,
+            behind_bishop
+        );
+        assert_eq!(
+            board.attack_map_without(Square::E4.into(), Color::White) & u64::from(Square::D5),
+            0
+        );
+    }
+
+    #[test]
+    fn test_debug_masks() {
+        let board = Chessboard::new();
+        let masks = board.debug_masks();
+
+        assert_eq!(
+            format_mask(masks.static_white_attack_mask),
+            ". . . . . . . . \n\
+             . . . . . . . . \n\
+             . . . . . . . . \n\
+             . . . . . . . . \n\
+             . . . . . . . . \n\
+             # # # # # # # # \n\
+             . . . # # # . . \n\
+             . . . # . # . . \n"
+        );
+        assert_eq!(masks.static_white_attack_mask & RANK_3, RANK_3);
+        assert_eq!(masks.static_white_attack_mask & RANK_6, 0);
+        assert_eq!(masks.static_black_attack_mask & RANK_6, RANK_6);
+        assert_eq!(masks.dynamic_white_attack_mask, 0xDF56);
+        assert_eq!(masks.dynamic_black_attack_mask, 0x56DF << 48);
+
+        let board = Chessboard::from_fen("4k3/8/8/8/3B4/8/8/4K3 w - - 0 1");
+        let masks = board.debug_masks();
+
+        assert_eq!(
+            format_mask(masks.dynamic_white_attack_mask),
+            ". . . . . . . # \n\
+             # . . . . . # . \n\
+             . # . . . # . . \n\
+             . . # . # . . . \n\
+             . . . . . . . . \n\
+             . . # . # . . . \n\
+             . # . . . # . . \n\
+             # . . . . . # . \n"
+        );
+    }
+
+    #[test]
+    fn test_state_snapshot() {
+        let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
         let mut board = Chessboard::from_fen(fen);
+        let snapshot = board.state_snapshot();
 
-        println!("{}", board.ascii());
+        board.turn = false;
+        board.castle_rights = [false; 4];
+        board.en_passant_square = None;
+        board.half_move = 12;
+        board.full_move = 30;
 
-        board.move_to("dxc8=Q#");
+        assert_ne!(board.state_snapshot(), snapshot);
 
-        println!("{}", board.ascii());
+        board.restore_state(snapshot);
 
-        assert_eq!(board.get_fen(), "2Q3k1/4R3/8/8/8/8/8/6K1 b - - 0 1");
+        assert_eq!(board.state_snapshot(), snapshot);
+        assert_eq!(board.get_fen(), fen);
+        assert!(board.legal_moves().contains(&"exf6".to_string()));
+    }
 
-        assert!(board.is_mate());
+    #[test]
+    fn test_restore_state_mismatched_rights() {
+        let mut board = Chessboard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let snapshot = board.state_snapshot();
+
+        board.move_to("Ra2").unwrap();
+        board.restore_state(snapshot);
+
+        assert_eq!(board.get_fen(), "r3k2r/8/8/8/8/8/R7/4K2R w Kkq - 0 1");
+        assert!(!board.legal_moves().contains(&"O-O-O".to_string()));
+        assert_eq!(board.move_to("O-O-O").unwrap_err(), MoveError::IllegalMove);
+        assert_eq!(board.pieces[Piece::ROOK as usize].count_ones(), 4);
     }
 
     #[test]
@@ -1635,5 +5402,13 @@ mod tests {
 
         let legal_moves = board.legal_moves();
         println!("{:?}", legal_moves);
+
+        assert_eq!(
+            legal_moves,
+            vec![
+                "Na3", "Nc3", "Nf3", "Nh3", "a3", "a4", "b3", "b4", "c3", "c4", "d3", "d4", "e3",
+                "e4", "f3", "f4", "g3", "g4", "h3", "h4"
+            ]
+        );
     }
 }
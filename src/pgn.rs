@@ -0,0 +1,383 @@
+use std::time::Duration;
+
+use crate::{fen, ChessMove, Chessboard, Color, FenError, START_FEN};
+
+/// An error returned when a game in PGN isn't consistent. Plies are counted from 0, like the
+/// indices of `Chessboard::history`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError {
+    /// The `FEN` tag doesn't describe a valid position.
+    InvalidFen(FenError),
+    /// The move at the ply can't be parsed or isn't legal.
+    IllegalMove(usize),
+    /// The result doesn't match the position after the given number of plies, such as a win
+    /// claimed in a stalemate or a draw claimed in a checkmate.
+    InconsistentResult(usize),
+}
+
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameError::InvalidFen(error) => write!(f, "Invalid FEN tag: {}", error),
+            GameError::IllegalMove(ply) => write!(f, "Illegal move at ply {}", ply),
+            GameError::InconsistentResult(ply) => {
+                write!(
+                    f,
+                    "Result is inconsistent with the position after ply {}",
+                    ply
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+pub(crate) struct ParsedPgn {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: Option<String>,
+}
+
+impl ParsedPgn {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) fn parse(pgn: &str) -> ParsedPgn {
+    let mut tags = Vec::new();
+    let mut moves = Vec::new();
+    let mut result = None;
+    let mut chars = pgn.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '[' => {
+                let mut tag = String::new();
+                let mut in_quotes = false;
+                for c in chars.by_ref() {
+                    match c {
+                        '"' => in_quotes = !in_quotes,
+                        ']' if !in_quotes => break,
+                        _ => {}
+                    }
+                    tag.push(c);
+                }
+
+                if let Some((name, value)) = tag.split_once(char::is_whitespace) {
+                    let value = value.trim().trim_matches('"').replace("\\\"", "\"");
+                    tags.push((name.to_string(), value));
+                }
+            }
+            '{' => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            ';' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '(' => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => depth -= 1,
+                        Some('{') => {
+                            for c in chars.by_ref() {
+                                if c == '}' {
+                                    break;
+                                }
+                            }
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut token = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '{' | '(' | ')' | ';' | '[') {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+
+                match token.as_str() {
+                    "1-0" | "0-1" | "1/2-1/2" | "*" => result = Some(token),
+                    _ if token.starts_with('$') => {}
+                    _ => {
+                        // A move number is digits followed by "." or "...", so castling written
+                        // as "0-0" keeps its zeros.
+                        let digits = token.trim_start_matches(|c: char| c.is_ascii_digit());
+                        let san = match digits.len() < token.len() && digits.starts_with('.') {
+                            true => digits.trim_start_matches('.'),
+                            false => token.as_str(),
+                        };
+
+                        if !san.is_empty() {
+                            moves.push(san.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    ParsedPgn {
+        tags,
+        moves,
+        result,
+    }
+}
+
+/// Replays a game in PGN and checks that every move is legal and that the result agrees with the
+/// final position. A decisive result can't be claimed for the side that is checkmated or in a
+/// stalemate, and a draw can't be claimed in a checkmate. Resignations and agreed draws are
+/// accepted since the board can't contradict them.
+/// # Examples
+/// ```
+/// use w_chess::{validate_game, GameError};
+/// assert_eq!(validate_game("1. f3 e5 2. g4 Qh4# 0-1"), Ok(()));
+/// assert_eq!(validate_game("1. e4 e5 2. Ke3 *"), Err(GameError::IllegalMove(2)));
+/// ```
+pub fn validate_game(pgn: &str) -> Result<(), GameError> {
+    let parsed = parse(pgn);
+    let board = replay(&parsed, |_, _| {})?;
+
+    let tag_result = parsed.tag("Result");
+    let result = parsed.result.as_deref().or(tag_result);
+    let tags_disagree = matches!(
+        (parsed.result.as_deref(), tag_result),
+        (Some(movetext_result), Some(tag_result)) if movetext_result != tag_result
+    );
+
+    let consistent = match result {
+        Some("1-0") => !(board.is_stalemate() || (board.is_mate() && board.turn() == Color::White)),
+        Some("0-1") => !(board.is_stalemate() || (board.is_mate() && board.turn() == Color::Black)),
+        Some("1/2-1/2") => !board.is_mate(),
+        _ => true,
+    };
+
+    if !consistent || tags_disagree {
+        return Err(GameError::InconsistentResult(parsed.moves.len()));
+    }
+
+    Ok(())
+}
+
+/// Plays the moves of a parsed game from its `FEN` tag or the starting position, calling `f` with
+/// the board and the move after each ply.
+pub(crate) fn replay<F: FnMut(&Chessboard, &ChessMove)>(
+    parsed: &ParsedPgn,
+    mut f: F,
+) -> Result<Chessboard, GameError> {
+    let mut board = match parsed.tag("FEN") {
+        Some(fen) => {
+            fen::validate(fen).map_err(GameError::InvalidFen)?;
+            Chessboard::from_fen(fen)
+        }
+        None => Chessboard::new(),
+    };
+
+    for (ply, san) in parsed.moves.iter().enumerate() {
+        board.play(san).map_err(|_| GameError::IllegalMove(ply))?;
+        f(&board, board.history.last().unwrap());
+    }
+
+    Ok(board)
+}
+
+pub(crate) fn movetext(history: &[ChessMove]) -> String {
+    let mut movetext = String::new();
+
+    let first_move = match history.first() {
+        Some(first_move) => first_move,
+        None => return movetext,
+    };
+
+    let parts: Vec<&str> = first_move.before().split_whitespace().collect();
+    let mut turn = parts[1] == "w";
+    let mut full_move: u32 = parts[5].parse().unwrap_or(1);
+
+    let mut after_comment = false;
+
+    for (index, chess_move) in history.iter().enumerate() {
+        if index > 0 {
+            movetext.push(' ');
+        }
+
+        if turn {
+            movetext.push_str(&format!("{}. ", full_move));
+        } else if index == 0 || after_comment {
+            movetext.push_str(&format!("{}... ", full_move));
+        }
+
+        movetext.push_str(chess_move.san());
+
+        after_comment = match chess_move.clock() {
+            Some(clock) => {
+                movetext.push_str(&format!(" {{[%clk {}]}}", format_clock(clock)));
+                true
+            }
+            None => false,
+        };
+
+        if !turn {
+            full_move += 1;
+        }
+        turn = !turn;
+    }
+
+    movetext
+}
+
+fn format_clock(clock: Duration) -> String {
+    let seconds = clock.as_secs();
+
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+pub(crate) fn to_pgn(history: &[ChessMove]) -> String {
+    let mut pgn = String::new();
+
+    if let Some(first_move) = history.first() {
+        if first_move.before() != START_FEN {
+            pgn.push_str("[SetUp \"1\"]\n");
+            pgn.push_str(&format!("[FEN \"{}\"]\n\n", first_move.before()));
+        }
+    }
+
+    let movetext = movetext(history);
+    if !movetext.is_empty() {
+        pgn.push_str(&movetext);
+        pgn.push(' ');
+    }
+    pgn.push('*');
+
+    pgn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Chessboard;
+
+    #[test]
+    fn test_movetext() {
+        let mut board = Chessboard::new();
+        board.move_to("e4").unwrap();
+        board.move_to("e5").unwrap();
+        board.move_to("Nf3").unwrap();
+
+        assert_eq!(board.to_pgn(), "1. e4 e5 2. Nf3 *");
+    }
+
+    #[test]
+    fn test_clock_comments() {
+        let mut board = Chessboard::new();
+        board.move_to("e4").unwrap();
+        board.move_to("e5").unwrap();
+        board.move_to("Nf3").unwrap();
+
+        board.set_clock(0, Duration::from_secs(300));
+        board.set_clock(2, Duration::from_secs(3723));
+
+        assert_eq!(
+            board.to_pgn(),
+            "1. e4 {[%clk 0:05:00]} 1... e5 2. Nf3 {[%clk 1:02:03]} *"
+        );
+        assert_eq!(board.history[0].clock(), Some(Duration::from_secs(300)));
+        assert_eq!(board.history[1].clock(), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = parse(
+            "[Event \"Casual\"]\n[Result \"1-0\"]\n\n1. e4 {best by test} e5 2.Nf3 $1 (2. f4 exf4) 2... Nc6 ; comment\n3. Bb5 1-0",
+        );
+
+        assert_eq!(parsed.tag("Event"), Some("Casual"));
+        assert_eq!(parsed.moves, vec!["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(parsed.result.as_deref(), Some("1-0"));
+    }
+
+    #[test]
+    fn test_parse_zero_castling() {
+        let pgn = "1. e4 e5 2. Nf3 Nc6 3. Bc4 d6 4.0-0 Be6 5. d3 Qd7 6. Nc3 0-0-0 *";
+        let parsed = parse(pgn);
+
+        assert_eq!(parsed.moves[6], "0-0");
+        assert_eq!(parsed.moves[11], "0-0-0");
+
+        let board = Chessboard::replay_pgn(pgn, |_, _| {}).unwrap();
+        assert_eq!(board.history.len(), 12);
+        assert_eq!(
+            board.get_fen(),
+            "2kr1bnr/pppq1ppp/2npb3/4p3/2B1P3/2NP1N2/PPP2PPP/R1BQ1RK1 w - - 3 7"
+        );
+    }
+
+    #[test]
+    fn test_validate_game() {
+        assert_eq!(validate_game("1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 1-0"), Ok(()));
+        assert_eq!(validate_game("1. f3 e5 2. g4 Qh4# 0-1"), Ok(()));
+        assert_eq!(
+            validate_game("1. e4 e5 2. Nf3 Nc6 3. Bc5 a6 *"),
+            Err(GameError::IllegalMove(4))
+        );
+        assert_eq!(
+            validate_game("1. f3 e5 2. g4 Qh4# 1-0"),
+            Err(GameError::InconsistentResult(4))
+        );
+        assert_eq!(
+            validate_game("1. f3 e5 2. g4 Qh4# 1/2-1/2"),
+            Err(GameError::InconsistentResult(4))
+        );
+        assert_eq!(
+            validate_game("[Result \"0-1\"]\n1. e4 e5 1-0"),
+            Err(GameError::InconsistentResult(2))
+        );
+        assert_eq!(
+            validate_game("[FEN \"7k/5Q2/6K1/8/8/8/8/8 b - - 0 1\"]\n1/2-1/2"),
+            Ok(())
+        );
+        assert_eq!(
+            validate_game("[FEN \"7k/5Q2/6K1/8/8/8/8/8 b - - 0 1\"]\n1-0"),
+            Err(GameError::InconsistentResult(0))
+        );
+        assert_eq!(
+            validate_game("[FEN \"7k/5Q2/6K1/8/8/8/8 b - - 0 1\"]\n*"),
+            Err(GameError::InvalidFen(FenError::InvalidPlacement))
+        );
+    }
+
+    #[test]
+    fn test_movetext_from_fen() {
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 20";
+        let board = Chessboard::from_fen_with_moves(fen, &["Nf6", "Nc3"]);
+
+        assert_eq!(
+            board.to_pgn(),
+            format!("[SetUp \"1\"]\n[FEN \"{}\"]\n\n20... Nf6 21. Nc3 *", fen)
+        );
+    }
+}
@@ -9,6 +9,20 @@ pub enum Piece {
     UNKNOWN = 6,
 }
 
+impl Piece {
+    pub(crate) fn san_symbol(&self) -> char {
+        match self {
+            Piece::PAWN => 'P',
+            Piece::BISHOP => 'B',
+            Piece::KNIGHT => 'N',
+            Piece::ROOK => 'R',
+            Piece::QUEEN => 'Q',
+            Piece::KING => 'K',
+            Piece::UNKNOWN => '?',
+        }
+    }
+}
+
 impl std::fmt::Display for Piece {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let symbol = match self {
@@ -0,0 +1,27 @@
+use crate::{Color, Piece, Square};
+
+/// A position described without FEN, for setting up a board programmatically.
+/// `board` is indexed by `[rank][file]`, so `board[0][0]` is a1 and `board[7][7]` is h8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub board: [[Option<(Piece, Color)>; 8]; 8],
+    pub turn: Color,
+    /// Castling rights in `KQkq` order.
+    pub castling: [bool; 4],
+    pub en_passant: Option<Square>,
+    pub halfmove: u32,
+    pub fullmove: u32,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self {
+            board: [[None; 8]; 8],
+            turn: Color::White,
+            castling: [false; 4],
+            en_passant: None,
+            halfmove: 0,
+            fullmove: 1,
+        }
+    }
+}
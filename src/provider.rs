@@ -0,0 +1,68 @@
+use crate::{Chessboard, MoveError, MoveInfo};
+
+/// A source of legal moves that can play them, so front-ends can work with any board
+/// implementation behind one interface.
+pub trait MoveProvider {
+    /// Returns the legal moves of the current position.
+    fn legal(&self) -> Vec<MoveInfo>;
+
+    /// Plays a move returned by `legal`.
+    fn make(&mut self, mv: MoveInfo) -> Result<(), MoveError>;
+
+    /// Returns the FEN of the current position.
+    fn fen(&self) -> String;
+}
+
+impl MoveProvider for Chessboard {
+    fn legal(&self) -> Vec<MoveInfo> {
+        self.legal_move_list()
+    }
+
+    fn make(&mut self, mv: MoveInfo) -> Result<(), MoveError> {
+        if !self.legal_move_list().contains(&mv) {
+            return Err(MoveError::IllegalMove);
+        }
+
+        let mut san = self.san_without_suffix(&mv);
+        if !self.apply_move_info(&mv, san.clone()) {
+            return Err(MoveError::IllegalMove);
+        }
+
+        san.push_str(self.check_suffix());
+        self.history.last_mut().unwrap().set_san(san);
+
+        Ok(())
+    }
+
+    fn fen(&self) -> String {
+        self.get_fen()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    #[test]
+    fn test_move_provider() {
+        let mut board = Chessboard::new();
+        let provider: &mut dyn MoveProvider = &mut board;
+
+        assert_eq!(provider.legal().len(), 20);
+
+        let mv = provider
+            .legal()
+            .into_iter()
+            .find(|mv| mv.from == Square::E2 && mv.to == Square::E4)
+            .unwrap();
+        provider.make(mv).unwrap();
+
+        assert_eq!(
+            provider.fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+        );
+        assert_eq!(provider.make(mv), Err(MoveError::IllegalMove));
+        assert_eq!(board.history.last().unwrap().san(), "e4");
+    }
+}
@@ -0,0 +1,140 @@
+use rand::Rng;
+
+use crate::{Chessboard, Color, Piece, Position};
+
+const PIECES: [Piece; 5] = [
+    Piece::PAWN,
+    Piece::KNIGHT,
+    Piece::BISHOP,
+    Piece::ROOK,
+    Piece::QUEEN,
+];
+
+impl Chessboard {
+    /// Returns a random legal position with both kings and at most `max_pieces` pieces in total,
+    /// kings included. Pawns are never placed on the first or last rank and there are no
+    /// castling rights or en passant square. Positions are drawn again until
+    /// `is_position_legal` holds. Both kings are always placed, so a `max_pieces` below 2 is
+    /// treated as 2.
+    /// # Examples
+    /// ```
+    /// use rand::SeedableRng;
+    /// use w_chess::Chessboard;
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    /// let board = Chessboard::random_position(&mut rng, 8);
+    /// assert!(board.is_position_legal());
+    /// ```
+    pub fn random_position(rng: &mut impl Rng, max_pieces: usize) -> Chessboard {
+        let max_pieces = max_pieces.max(2);
+
+        loop {
+            let mut position = Position {
+                turn: Color::from(rng.gen_bool(0.5)),
+                ..Position::default()
+            };
+
+            for color in [Color::White, Color::Black] {
+                place(rng, &mut position, Piece::KING, color);
+            }
+
+            for _ in 0..rng.gen_range(0..=max_pieces - 2) {
+                let piece = PIECES[rng.gen_range(0..PIECES.len())];
+                let color = Color::from(rng.gen_bool(0.5));
+                place(rng, &mut position, piece, color);
+            }
+
+            let board = Chessboard::from_position(&position);
+            if board.is_position_legal() {
+                return board;
+            }
+        }
+    }
+}
+
+fn place(rng: &mut impl Rng, position: &mut Position, piece: Piece, color: Color) {
+    loop {
+        let rank = match piece {
+            Piece::PAWN => rng.gen_range(1..7),
+            _ => rng.gen_range(0..8),
+        };
+        let file = rng.gen_range(0..8);
+
+        if position.board[rank][file].is_none() {
+            position.board[rank][file] = Some((piece, color));
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_random_position() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        for _ in 0..500 {
+            let board = Chessboard::random_position(&mut rng, 12);
+
+            assert!(board.is_position_legal());
+            assert!(board.occupied().count_ones() <= 12);
+        }
+
+        for max_pieces in 0..=2 {
+            let board = Chessboard::random_position(&mut rng, max_pieces);
+            assert!(board.is_position_legal());
+            assert_eq!(board.occupied().count_ones(), 2);
+        }
+    }
+
+    #[test]
+    fn test_static_attack_masks_match_recompute() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        for game in 0..40 {
+            let mut board = match game % 2 {
+                0 => Chessboard::new(),
+                _ => Chessboard::random_position(&mut rng, 16),
+            };
+
+            for _ in 0..rng.gen_range(0..60) {
+                let moves = board.legal_moves();
+                if moves.is_empty() {
+                    break;
+                }
+                board
+                    .move_to(&moves[rng.gen_range(0..moves.len())])
+                    .unwrap();
+
+                let fresh = Chessboard::from_fen(&board.get_fen());
+                assert_eq!(
+                    (
+                        board.static_white_attack_mask,
+                        board.static_black_attack_mask
+                    ),
+                    (
+                        fresh.static_white_attack_mask,
+                        fresh.static_black_attack_mask
+                    ),
+                    "{}",
+                    board.get_fen()
+                );
+                assert_eq!(board.legal_move_list(), fresh.legal_move_list());
+            }
+
+            while board.undo_move().is_some() {
+                assert_eq!(
+                    board.static_white_attack_mask,
+                    board.generate_static_attack_mask(true)
+                );
+                assert_eq!(
+                    board.static_black_attack_mask,
+                    board.generate_static_attack_mask(false)
+                );
+            }
+        }
+    }
+}
@@ -16,6 +16,8 @@ pub const FILE_F: u64 = 0b001000000010000000100000001000000010000000100000001000
 pub const FILE_G: u64 = 0b0100000001000000010000000100000001000000010000000100000001000000;
 pub const FILE_H: u64 = 0b1000000010000000100000001000000010000000100000001000000010000000;
 
+pub const DARK_SQUARES: u64 = 0b1010101001010101101010100101010110101010010101011010101001010101;
+
 pub const WHITE_KING_SIDE_CASTLE: u64 =
     0b0000000000000000000000000000000000000000000000000000000001100000;
 pub const WHITE_QUEEN_SIDE_CASTLE: u64 =
@@ -107,6 +109,67 @@ pub enum Square {
     UNKNOWN,
 }
 
+impl Square {
+    /// Returns the file and rank characters of the square, or `('-', '-')` for an unknown square.
+    /// # Examples
+    /// ```
+    /// use w_chess::Square;
+    /// assert_eq!(Square::E4.to_coords(), ('e', '4'));
+    /// ```
+    pub fn to_coords(self) -> (char, char) {
+        match self {
+            Square::UNKNOWN => ('-', '-'),
+            _ => {
+                let index = self as u8;
+                ((b'a' + index % 8) as char, (b'1' + index / 8) as char)
+            }
+        }
+    }
+
+    /// Returns the file of the square as a bitboard, or 0 for an unknown square.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Square, FILE_E};
+    /// assert_eq!(Square::E4.file_mask(), FILE_E);
+    /// ```
+    pub fn file_mask(self) -> u64 {
+        match self {
+            Square::UNKNOWN => 0,
+            _ => FILE_A << (self as u8 % 8),
+        }
+    }
+
+    /// Returns the rank of the square as a bitboard, or 0 for an unknown square.
+    /// # Examples
+    /// ```
+    /// use w_chess::{Square, RANK_4};
+    /// assert_eq!(Square::E4.rank_mask(), RANK_4);
+    /// ```
+    pub fn rank_mask(self) -> u64 {
+        match self {
+            Square::UNKNOWN => 0,
+            _ => RANK_1 << (self as u8 / 8 * 8),
+        }
+    }
+
+    /// Returns the square with the given file and rank characters, if they are on the board.
+    /// # Examples
+    /// ```
+    /// use w_chess::Square;
+    /// assert_eq!(Square::from_coords('e', '4'), Some(Square::E4));
+    /// assert_eq!(Square::from_coords('i', '4'), None);
+    /// ```
+    pub fn from_coords(file: char, rank: char) -> Option<Square> {
+        match (file, rank) {
+            ('a'..='h', '1'..='8') => {
+                let index = (rank as u8 - b'1') * 8 + (file as u8 - b'a');
+                Some(Square::from(1u64 << index))
+            }
+            _ => None,
+        }
+    }
+}
+
 impl From<&str> for Square {
     fn from(value: &str) -> Self {
         match value {
@@ -325,9 +388,9 @@ impl std::fmt::Display for Square {
     }
 }
 
-impl Into<u64> for Square {
-    fn into(self) -> u64 {
-        match self {
+impl From<Square> for u64 {
+    fn from(square: Square) -> Self {
+        match square {
             Square::A1 => 0b0000000000000000000000000000000000000000000000000000000000000001,
             Square::B1 => 0b0000000000000000000000000000000000000000000000000000000000000010,
             Square::C1 => 0b0000000000000000000000000000000000000000000000000000000000000100,
@@ -396,3 +459,43 @@ impl Into<u64> for Square {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coords_round_trip() {
+        for index in 0..64 {
+            let square = Square::from(1u64 << index);
+            let (file, rank) = square.to_coords();
+
+            assert_eq!(format!("{}{}", file, rank), square.to_string());
+            assert_eq!(Square::from_coords(file, rank), Some(square));
+        }
+
+        assert_eq!(Square::UNKNOWN.to_coords(), ('-', '-'));
+    }
+
+    #[test]
+    fn test_file_and_rank_masks() {
+        let files = [
+            FILE_A, FILE_B, FILE_C, FILE_D, FILE_E, FILE_F, FILE_G, FILE_H,
+        ];
+        let ranks = [
+            RANK_1, RANK_2, RANK_3, RANK_4, RANK_5, RANK_6, RANK_7, RANK_8,
+        ];
+
+        for index in 0..64 {
+            let square = Square::from(1u64 << index);
+            assert_eq!(square.file_mask(), files[index % 8]);
+            assert_eq!(square.rank_mask(), ranks[index / 8]);
+        }
+
+        assert_eq!(Square::E4.file_mask(), FILE_E);
+        assert_eq!(Square::UNKNOWN.file_mask(), 0);
+        assert_eq!(Square::UNKNOWN.rank_mask(), 0);
+        assert_eq!(Square::from_coords('a', '9'), None);
+        assert_eq!(Square::from_coords('E', '4'), None);
+    }
+}
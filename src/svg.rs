@@ -0,0 +1,157 @@
+use crate::{Chessboard, Piece};
+
+const LIGHT: &str = "#f0d9b5";
+const DARK: &str = "#b58863";
+
+impl Chessboard {
+    /// Returns an SVG rendering of the current position, `size` pixels wide and high, oriented
+    /// like `ascii()` with white at the bottom.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let svg = Chessboard::new().to_svg(400);
+    /// assert!(svg.starts_with("<svg"));
+    /// ```
+    pub fn to_svg(&self, size: u32) -> String {
+        self.to_svg_with_colors(size, LIGHT, DARK)
+    }
+
+    /// Returns an SVG rendering of the current position like `to_svg`, using the given colors
+    /// for the light and dark squares.
+    /// # Examples
+    /// ```
+    /// use w_chess::Chessboard;
+    /// let svg = Chessboard::new().to_svg_with_colors(400, "#eeeeee", "#769656");
+    /// assert!(svg.contains("#769656"));
+    /// ```
+    pub fn to_svg_with_colors(&self, size: u32, light: &str, dark: &str) -> String {
+        let square_size = size as f64 / 8.0;
+        let label_size = round(square_size / 5.0);
+        let padding = square_size / 20.0;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            size, size, size, size
+        );
+
+        for row in 0..8_u32 {
+            for file in 0..8_u32 {
+                let rank = 7 - row;
+                let x = file as f64 * square_size;
+                let y = row as f64 * square_size;
+                let (fill, label_fill) = match (file + rank) % 2 {
+                    0 => (dark, light),
+                    _ => (light, dark),
+                };
+
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                    round(x),
+                    round(y),
+                    round(square_size),
+                    round(square_size),
+                    fill
+                ));
+
+                if file == 0 {
+                    svg.push_str(&format!(
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\">{}</text>\n",
+                        round(x + padding),
+                        round(y + label_size),
+                        label_size,
+                        label_fill,
+                        rank + 1
+                    ));
+                }
+                if rank == 0 {
+                    svg.push_str(&format!(
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" text-anchor=\"end\">{}</text>\n",
+                        round(x + square_size - padding),
+                        round(y + square_size - padding),
+                        label_size,
+                        label_fill,
+                        (b'a' + file as u8) as char
+                    ));
+                }
+
+                let square: u64 = 1 << (rank * 8 + file);
+                if square & self.all() != 0 {
+                    svg.push_str(&format!(
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                        round(x + square_size / 2.0),
+                        round(y + square_size / 2.0),
+                        round(square_size * 0.75),
+                        glyph(self.get_piece(square), self.white & square != 0)
+                    ));
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Rounds a coordinate to two decimals so the output doesn't carry floating point noise.
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn glyph(piece: Piece, white: bool) -> char {
+    match (piece, white) {
+        (Piece::PAWN, true) => '\u{2659}',
+        (Piece::KNIGHT, true) => '\u{2658}',
+        (Piece::BISHOP, true) => '\u{2657}',
+        (Piece::ROOK, true) => '\u{2656}',
+        (Piece::QUEEN, true) => '\u{2655}',
+        (Piece::KING, true) => '\u{2654}',
+        (Piece::PAWN, false) => '\u{265F}',
+        (Piece::KNIGHT, false) => '\u{265E}',
+        (Piece::BISHOP, false) => '\u{265D}',
+        (Piece::ROOK, false) => '\u{265C}',
+        (Piece::QUEEN, false) => '\u{265B}',
+        (Piece::KING, false) => '\u{265A}',
+        (Piece::UNKNOWN, _) => ' ',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_svg() {
+        let svg = Chessboard::new().to_svg(400);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 64);
+        assert_eq!(svg.matches('\u{2654}').count(), 1);
+        assert_eq!(svg.matches('\u{265F}').count(), 8);
+    }
+
+    #[test]
+    fn test_to_svg_fills_size() {
+        let svg = Chessboard::new().to_svg(100);
+        assert!(svg.contains(
+            "<rect x=\"87.5\" y=\"87.5\" width=\"12.5\" height=\"12.5\" fill=\"#f0d9b5\"/>"
+        ));
+
+        let svg = Chessboard::new().to_svg(4);
+        assert!(
+            svg.contains("<rect x=\"3.5\" y=\"0\" width=\"0.5\" height=\"0.5\" fill=\"#b58863\"/>")
+        );
+    }
+
+    #[test]
+    fn test_to_svg_orientation() {
+        let svg = Chessboard::from_fen("7k/8/8/8/8/8/8/K7 w - - 0 1").to_svg(80);
+
+        assert!(
+            svg.contains("<rect x=\"0\" y=\"70\" width=\"10\" height=\"10\" fill=\"#b58863\"/>")
+        );
+        assert!(svg.contains("y=\"75\" font-size=\"7.5\" text-anchor=\"middle\" dominant-baseline=\"central\">\u{2654}"));
+        assert!(svg.contains(
+            "y=\"5\" font-size=\"7.5\" text-anchor=\"middle\" dominant-baseline=\"central\">\u{265A}"
+        ));
+    }
+}
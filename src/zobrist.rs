@@ -0,0 +1,45 @@
+const fn split_mix(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate_keys() -> [u64; 781] {
+    let mut keys = [0; 781];
+    let mut state = 0x5EED_C0FF_EE15_600D;
+    let mut index = 0;
+
+    while index < keys.len() {
+        let (next_state, key) = split_mix(state);
+        state = next_state;
+        keys[index] = key;
+        index += 1;
+    }
+
+    keys
+}
+
+const KEYS: [u64; 781] = generate_keys();
+
+/// Returns the key of a piece of the given color on the square index.
+pub const fn piece_key(piece: usize, color: bool, square: usize) -> u64 {
+    let color = if color { 0 } else { 1 };
+    KEYS[(color * 6 + piece) * 64 + square]
+}
+
+/// Returns the key mixed in when black is to move.
+pub const fn turn_key() -> u64 {
+    KEYS[768]
+}
+
+/// Returns the key of a castling right, in `KQkq` order.
+pub const fn castling_key(index: usize) -> u64 {
+    KEYS[769 + index]
+}
+
+/// Returns the key of the en passant file.
+pub const fn en_passant_key(file: usize) -> u64 {
+    KEYS[773 + file]
+}